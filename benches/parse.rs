@@ -0,0 +1,78 @@
+//! Compare parsing into `Config` and into the borrowed `ConfigRef`.
+//!
+//! Run with `cargo bench --bench parse`.
+
+extern crate resolv_conf;
+
+use resolv_conf::{Config, ConfigRef};
+use std::fs;
+use std::time::{Duration, Instant};
+
+/// Keep the optimizer from removing a computation, like `std::hint::black_box`
+/// which is too recent for the minimum supported Rust version
+fn black_box<T>(value: T) -> T {
+    unsafe {
+        let copy = std::ptr::read_volatile(&value);
+        std::mem::forget(value);
+        copy
+    }
+}
+
+/// Run `f` repeatedly for about a second and return the time per iteration
+fn measure<F: FnMut()>(mut f: F) -> Duration {
+    // warm up, and find how many iterations fit in 100ms
+    let mut iterations = 1u32;
+    loop {
+        let start = Instant::now();
+        for _ in 0..iterations {
+            f();
+        }
+        if start.elapsed() > Duration::from_millis(100) {
+            break;
+        }
+        iterations *= 2;
+    }
+
+    iterations *= 10;
+    let start = Instant::now();
+    for _ in 0..iterations {
+        f();
+    }
+    start.elapsed() / iterations
+}
+
+fn main() {
+    // what kubelet writes in every pod
+    let mut files = vec![(
+        "kubernetes pod".to_string(),
+        b"search default.svc.cluster.local svc.cluster.local cluster.local \
+          us-east-1.compute.internal ec2.internal corp.example.com\n\
+          nameserver 10.96.0.10\n\
+          options ndots:5\n"
+            .to_vec(),
+    )];
+    for name in &["simple", "linux", "macos", "openbsd"] {
+        let path = format!("tests/resolv.conf-{}", name);
+        files.push((path.clone(), fs::read(&path).unwrap()));
+    }
+
+    println!(
+        "{:<28} {:>12} {:>12} {:>8}",
+        "file", "Config", "ConfigRef", "speedup"
+    );
+    for (path, buf) in &files {
+        let owned = measure(|| {
+            black_box(Config::parse_with_errors(black_box(buf)));
+        });
+        let borrowed = measure(|| {
+            black_box(ConfigRef::parse_with_errors(black_box(buf)));
+        });
+        println!(
+            "{:<28} {:>10}ns {:>10}ns {:>7.2}x",
+            path,
+            owned.as_nanos(),
+            borrowed.as_nanos(),
+            owned.as_secs_f64() / borrowed.as_secs_f64()
+        );
+    }
+}
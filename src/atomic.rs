@@ -0,0 +1,199 @@
+use std::ffi::OsString;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::{Config, Writer};
+
+/// Most symlinks followed before giving up, like Linux does
+const MAX_SYMLINKS: usize = 40;
+
+/// Distinguishes the temporary files of the threads of a process
+static TEMP_COUNTER: AtomicUsize = AtomicUsize::new(0);
+
+/// What [`Config::write_to_path`] does when the path is a symlink.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum SymlinkPolicy {
+    /// Replace the file the symlink points to, keeping the symlink
+    Follow,
+    /// Replace the symlink itself with a regular file
+    Replace,
+}
+
+/// How [`Config::write_to_path`] replaces a file.
+///
+/// There is no default: whether a symlinked resolv.conf, which usually
+/// belongs to another program, is written through or replaced must be
+/// chosen.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct WriteOptions {
+    symlinks: SymlinkPolicy,
+    backup: bool,
+}
+
+impl WriteOptions {
+    /// Handle symlinks with `symlinks`, and keep no backup.
+    pub fn new(symlinks: SymlinkPolicy) -> Self {
+        Self {
+            symlinks,
+            backup: false,
+        }
+    }
+
+    /// Keep the replaced file as `<name>.bak`, next to it, replacing any
+    /// previous backup.
+    pub fn backup(mut self, backup: bool) -> Self {
+        self.backup = backup;
+        self
+    }
+}
+
+impl Config {
+    /// Write the configuration to `path` atomically: readers see either the
+    /// previous file or the new one, never a truncated one.
+    ///
+    /// The text is written to a temporary file in the same directory,
+    /// synced to disk, and renamed over the target. The new file keeps the
+    /// permissions and, on Unix, the owner of the file it replaces; a new
+    /// file is readable by everyone, like resolv.conf must be.
+    ///
+    /// ```rust,no_run
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::{Config, SymlinkPolicy, WriteOptions};
+    /// # fn main() {
+    /// let config = Config::parse("nameserver 10.0.0.1").unwrap();
+    /// let options = WriteOptions::new(SymlinkPolicy::Replace).backup(true);
+    /// config.write_to_path("/etc/resolv.conf", options).unwrap();
+    /// # }
+    /// ```
+    pub fn write_to_path<P: AsRef<Path>>(&self, path: P, options: WriteOptions) -> io::Result<()> {
+        write_atomic(path.as_ref(), self.to_string().as_bytes(), options)
+    }
+}
+
+impl Writer {
+    /// Write `config` to `path` atomically, like
+    /// [`Config::write_to_path`].
+    pub fn write_to_path<P: AsRef<Path>>(
+        &self,
+        config: &Config,
+        path: P,
+        options: WriteOptions,
+    ) -> io::Result<()> {
+        write_atomic(path.as_ref(), self.format(config).as_bytes(), options)
+    }
+}
+
+/// Replace the file at `path` with `contents` atomically
+pub(crate) fn write_atomic(path: &Path, contents: &[u8], options: WriteOptions) -> io::Result<()> {
+    let target = match options.symlinks {
+        SymlinkPolicy::Follow => resolve_symlinks(path)?,
+        SymlinkPolicy::Replace => path.to_owned(),
+    };
+    let name = match target.file_name() {
+        Some(name) => name,
+        None => {
+            let msg = format!("{} is not a file name", target.display());
+            return Err(io::Error::new(io::ErrorKind::InvalidInput, msg));
+        }
+    };
+    let dir = match target.parent() {
+        Some(dir) if !dir.as_os_str().is_empty() => dir,
+        _ => Path::new("."),
+    };
+    // the permissions of the replaced file, or of the file the replaced
+    // symlink points to
+    let previous = fs::metadata(&target).ok();
+
+    let mut temp_name = OsString::from(".");
+    temp_name.push(name);
+    temp_name.push(format!(
+        ".tmp-{}-{}",
+        process::id(),
+        TEMP_COUNTER.fetch_add(1, Ordering::Relaxed)
+    ));
+    let temp = dir.join(temp_name);
+
+    let result = write_temp(&temp, contents, previous.as_ref()).and_then(|()| {
+        if options.backup && previous.is_some() {
+            let mut backup = target.clone().into_os_string();
+            backup.push(".bak");
+            backup_file(&target, Path::new(&backup))?;
+        }
+        fs::rename(&temp, &target)
+    });
+    if let Err(e) = result {
+        let _ = fs::remove_file(&temp);
+        return Err(e);
+    }
+
+    // make the rename itself durable
+    #[cfg(unix)]
+    fs::File::open(dir)?.sync_all()?;
+    Ok(())
+}
+
+/// Create `temp` with `contents` and the permissions of `previous`, and
+/// sync it to disk
+fn write_temp(temp: &Path, contents: &[u8], previous: Option<&fs::Metadata>) -> io::Result<()> {
+    let mut open = OpenOptions::new();
+    open.write(true).create_new(true);
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::OpenOptionsExt;
+        open.mode(0o644);
+    }
+    let mut file = open.open(temp)?;
+    file.write_all(contents)?;
+
+    if let Some(previous) = previous {
+        file.set_permissions(previous.permissions())?;
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::MetadataExt;
+            use std::os::unix::io::AsRawFd;
+            let current = file.metadata()?;
+            if (current.uid(), current.gid()) != (previous.uid(), previous.gid()) {
+                // SAFETY: the descriptor is owned by `file`, which is alive
+                if unsafe { libc::fchown(file.as_raw_fd(), previous.uid(), previous.gid()) } < 0 {
+                    return Err(io::Error::last_os_error());
+                }
+            }
+        }
+    }
+    file.sync_all()
+}
+
+/// Make `backup` a copy of `target`, replacing it if it exists
+fn backup_file(target: &Path, backup: &Path) -> io::Result<()> {
+    match fs::remove_file(backup) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => {}
+    }
+    // a hard link keeps the replaced file as it was, owner included
+    if fs::hard_link(target, backup).is_err() {
+        fs::copy(target, backup)?;
+    }
+    Ok(())
+}
+
+/// Follow the symlinks of `path`, even if the last one is dangling
+fn resolve_symlinks(path: &Path) -> io::Result<PathBuf> {
+    let mut path = path.to_owned();
+    for _ in 0..MAX_SYMLINKS {
+        match fs::symlink_metadata(&path) {
+            Ok(metadata) if metadata.file_type().is_symlink() => {
+                let link = fs::read_link(&path)?;
+                path = match path.parent() {
+                    Some(parent) => parent.join(link),
+                    None => link,
+                };
+            }
+            _ => return Ok(path),
+        }
+    }
+    let msg = format!("too many levels of symbolic links at {}", path.display());
+    Err(io::Error::new(io::ErrorKind::Other, msg))
+}
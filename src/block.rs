@@ -0,0 +1,261 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::ops::Range;
+use std::path::Path;
+
+use crate::atomic::{write_atomic, WriteOptions};
+use crate::{Config, ParseError};
+
+/// A section of a file owned by one program, between a begin and an end
+/// marker line.
+///
+/// Several programs can each own a block of `/etc/resolv.conf` or
+/// `/etc/hosts` without clobbering each other: editing a block leaves
+/// everything outside of it byte-identical, and setting a block to the
+/// contents it already has changes nothing.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::ManagedBlock;
+/// # fn main() {
+/// let block = ManagedBlock::new("vpn");
+/// let text = "nameserver 10.0.0.1\n";
+///
+/// let text = block.replace(text, "nameserver 10.8.0.1\n").unwrap();
+/// assert_eq!(text, "\
+/// nameserver 10.0.0.1
+/// ## BEGIN vpn
+/// nameserver 10.8.0.1
+/// ## END vpn
+/// ");
+/// assert_eq!(block.replace(&text, "nameserver 10.8.0.1").unwrap(), text);
+/// assert_eq!(block.contents(&text).unwrap(), Some("nameserver 10.8.0.1\n"));
+///
+/// assert_eq!(block.remove(&text).unwrap(), "nameserver 10.0.0.1\n");
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ManagedBlock {
+    begin: String,
+    end: String,
+    validate: bool,
+}
+
+/// Error while editing a [`ManagedBlock`]
+#[derive(Debug)]
+pub enum BlockError {
+    /// The begin marker at the given line has no end marker
+    Unterminated(usize),
+    /// The end marker at the given line has no begin marker
+    UnexpectedEnd(usize),
+    /// The block appears a second time at the given line
+    Duplicate(usize),
+    /// The new contents of the block contain one of its markers
+    MarkerInContents,
+    /// The new contents of the block can not be parsed as resolv.conf lines
+    Invalid(Vec<ParseError>),
+    /// The file could not be read or written
+    Io(io::Error),
+}
+
+impl fmt::Display for BlockError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Unterminated(line) => write!(f, "block at line {line} is not terminated"),
+            Self::UnexpectedEnd(line) => {
+                write!(f, "end of block at line {line} has no beginning")
+            }
+            Self::Duplicate(line) => write!(f, "block repeated at line {line}"),
+            Self::MarkerInContents => write!(f, "block contents contain a block marker"),
+            Self::Invalid(errors) => match errors.first() {
+                Some(err) => write!(f, "invalid block contents: {err}"),
+                None => write!(f, "invalid block contents"),
+            },
+            Self::Io(err) => write!(f, "failed to update file: {err}"),
+        }
+    }
+}
+
+impl Error for BlockError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Invalid(errors) => errors.first().map(|err| err as _),
+            Self::Io(err) => Some(err),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for BlockError {
+    fn from(err: io::Error) -> Self {
+        Self::Io(err)
+    }
+}
+
+impl ManagedBlock {
+    /// A block of `owner`, between `# BEGIN <owner>` and `# END <owner>`.
+    pub fn new(owner: &str) -> Self {
+        Self::with_markers(format!("# BEGIN {owner}"), format!("# END {owner}"))
+    }
+
+    /// A block between the given marker lines.
+    pub fn with_markers<B: Into<String>, E: Into<String>>(begin: B, end: E) -> Self {
+        Self {
+            begin: begin.into(),
+            end: end.into(),
+            validate: true,
+        }
+    }
+
+    /// Check that the new contents of the block parse as resolv.conf lines,
+    /// which is the default. Turn it off to edit other files, like
+    /// `/etc/hosts`.
+    pub fn validate(mut self, validate: bool) -> Self {
+        self.validate = validate;
+        self
+    }
+
+    /// Return the byte range of the block in `text`, from the start of its
+    /// begin marker to the end of its end marker line.
+    pub fn find(&self, text: &str) -> Result<Option<Range<usize>>, BlockError> {
+        let mut found = None;
+        let mut start = None;
+        let mut offset = 0;
+        for (lineno, line) in text.split_inclusive('\n').enumerate() {
+            let marker = line.trim_end();
+            if marker == self.begin {
+                if let Some((begin, _)) = start {
+                    return Err(BlockError::Unterminated(begin));
+                }
+                if found.is_some() {
+                    return Err(BlockError::Duplicate(lineno));
+                }
+                start = Some((lineno, offset));
+            } else if marker == self.end {
+                match start.take() {
+                    Some((_, begin)) => found = Some(begin..offset + line.len()),
+                    None => return Err(BlockError::UnexpectedEnd(lineno)),
+                }
+            }
+            offset += line.len();
+        }
+        match start {
+            Some((lineno, _)) => Err(BlockError::Unterminated(lineno)),
+            None => Ok(found),
+        }
+    }
+
+    /// Return the lines between the markers of the block, if `text` has it.
+    pub fn contents<'a>(&self, text: &'a str) -> Result<Option<&'a str>, BlockError> {
+        Ok(self.find(text)?.map(|range| {
+            let block = &text[range];
+            let start = block.find('\n').map_or(block.len(), |i| i + 1);
+            let end = block.trim_end().rfind('\n').map_or(start, |i| i + 1);
+            &block[start..end.max(start)]
+        }))
+    }
+
+    /// Set the contents of the block, adding it at the end of `text` if it
+    /// is not there yet.
+    pub fn replace(&self, text: &str, contents: &str) -> Result<String, BlockError> {
+        let mut block = String::new();
+        block.push_str(&self.begin);
+        block.push('\n');
+        for line in contents.lines() {
+            if line.trim_end() == self.begin || line.trim_end() == self.end {
+                return Err(BlockError::MarkerInContents);
+            }
+            block.push_str(line);
+            block.push('\n');
+        }
+        block.push_str(&self.end);
+        block.push('\n');
+
+        let mut result = String::with_capacity(text.len() + block.len() + 1);
+        let start = match self.find(text)? {
+            Some(range) => {
+                result.push_str(&text[..range.start]);
+                result.push_str(&block);
+                result.push_str(&text[range.end..]);
+                range.start
+            }
+            None => {
+                result.push_str(text);
+                if !text.is_empty() && !text.ends_with('\n') {
+                    result.push('\n');
+                }
+                let start = result.len();
+                result.push_str(&block);
+                start
+            }
+        };
+
+        if self.validate {
+            // the lines between the markers
+            let first = result[..start].matches('\n').count() + 1;
+            let lines = first..first + block.matches('\n').count() - 2;
+            let errors = Config::parse_with_errors(result.as_bytes())
+                .1
+                .into_iter()
+                .filter(|err| lines.contains(&error_line(err)))
+                .collect::<Vec<_>>();
+            if !errors.is_empty() {
+                return Err(BlockError::Invalid(errors));
+            }
+        }
+        Ok(result)
+    }
+
+    /// Remove the block from `text`, if it is there.
+    pub fn remove(&self, text: &str) -> Result<String, BlockError> {
+        Ok(match self.find(text)? {
+            Some(range) => [&text[..range.start], &text[range.end..]].concat(),
+            None => text.to_owned(),
+        })
+    }
+
+    /// Set the contents of the block in the file at `path`, or remove it if
+    /// `contents` is `None`, and return whether the file changed.
+    ///
+    /// The file is only written, atomically, if it changes. A missing file
+    /// is read as an empty one: it is created when a block is added, and
+    /// left missing when the block is removed.
+    pub fn update_path<P: AsRef<Path>>(
+        &self,
+        path: P,
+        contents: Option<&str>,
+        options: WriteOptions,
+    ) -> Result<bool, BlockError> {
+        let path = path.as_ref();
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(err) => return Err(err.into()),
+        };
+        let updated = match contents {
+            Some(contents) => self.replace(&text, contents)?,
+            None => self.remove(&text)?,
+        };
+        if updated == text {
+            return Ok(false);
+        }
+        write_atomic(path, updated.as_bytes(), options)?;
+        Ok(true)
+    }
+}
+
+/// The line a parse error was found at
+fn error_line(err: &ParseError) -> usize {
+    match *err {
+        ParseError::InvalidUtf8(line, _)
+        | ParseError::InvalidValue(line)
+        | ParseError::InvalidOptionValue(line)
+        | ParseError::InvalidOption(line)
+        | ParseError::InvalidDirective(line)
+        | ParseError::InvalidIp(line, _)
+        | ParseError::ExtraData(line)
+        | ParseError::LimitExceeded(line, _) => line,
+    }
+}
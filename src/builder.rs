@@ -0,0 +1,326 @@
+use std::error::Error;
+use std::fmt;
+
+use crate::options::set_options;
+use crate::{
+    Config, Family, LastSearch, Lookup, Network, ResolverOption, ScopedIp, NAMESERVER_LIMIT,
+    SEARCH_LIMIT,
+};
+
+/// Longest domain name, without its trailing dot
+const MAX_DOMAIN_LENGTH: usize = 253;
+
+/// A resolver implementation, whose limits a configuration can be checked
+/// against.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Dialect {
+    /// GNU libc
+    Glibc,
+    /// musl libc, which only knows the `ndots`, `timeout` and `attempts`
+    /// options
+    Musl,
+    /// OpenBSD, the only one reading `lookup` and `family`
+    OpenBsd,
+}
+
+impl Dialect {
+    /// The maximum number of search domains used
+    fn max_search(self) -> Option<usize> {
+        match self {
+            Self::Glibc | Self::OpenBsd => Some(SEARCH_LIMIT),
+            Self::Musl => None,
+        }
+    }
+
+    /// The maximum values of `ndots`, `timeout` and `attempts`
+    fn max_values(self) -> (u32, u32, u32) {
+        match self {
+            Self::Glibc | Self::OpenBsd => (15, 30, 5),
+            Self::Musl => (15, 60, 10),
+        }
+    }
+}
+
+impl fmt::Display for Dialect {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Glibc => "glibc",
+            Self::Musl => "musl",
+            Self::OpenBsd => "OpenBSD",
+        })
+    }
+}
+
+/// A reason why a configuration is invalid, as returned by
+/// [`Config::validate`] and [`ConfigBuilder::build`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ValidationError {
+    /// There are more nameservers than a resolver uses
+    TooManyNameservers {
+        /// The number of nameservers
+        count: usize,
+        /// The maximum number of nameservers
+        max: usize,
+    },
+    /// There are more search domains than the dialect uses
+    TooManySearchDomains {
+        /// The number of search domains
+        count: usize,
+        /// The maximum number of search domains
+        max: usize,
+    },
+    /// The search list is empty
+    EmptySearch,
+    /// The given domain is empty, too long, or can not be written in a
+    /// resolv.conf file
+    InvalidDomain(String),
+    /// The value of the given option is out of range
+    OutOfRange {
+        /// The name of the option
+        option: &'static str,
+        /// The value of the option
+        value: u32,
+        /// The minimum value
+        min: u32,
+        /// The maximum value
+        max: u32,
+    },
+    /// The given directive or option is not supported by the dialect
+    Unsupported(String, Dialect),
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::TooManyNameservers { count, max } => {
+                write!(f, "{count} nameservers, at most {max} are used")
+            }
+            Self::TooManySearchDomains { count, max } => {
+                write!(f, "{count} search domains, at most {max} are used")
+            }
+            Self::EmptySearch => write!(f, "empty search list"),
+            Self::InvalidDomain(domain) => write!(f, "invalid domain {domain:?}"),
+            Self::OutOfRange {
+                option,
+                value,
+                min,
+                max,
+            } => write!(f, "{option}:{value} is not between {min} and {max}"),
+            Self::Unsupported(what, dialect) => write!(f, "{what} is not supported by {dialect}"),
+        }
+    }
+}
+
+impl Error for ValidationError {}
+
+/// Build a [`Config`] and check it before it is used.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{ConfigBuilder, Dialect, ResolverOption, ValidationError};
+/// use std::net::Ipv4Addr;
+/// # fn main() {
+/// let config = ConfigBuilder::new()
+///     .nameserver(Ipv4Addr::new(10, 0, 0, 1))
+///     .search(["svc.cluster.local", "cluster.local"])
+///     .option(ResolverOption::Ndots(5))
+///     .build()
+///     .unwrap();
+/// assert_eq!(config.to_string(), "\
+/// nameserver 10.0.0.1
+/// search svc.cluster.local cluster.local
+/// options ndots:5
+/// ");
+///
+/// let errors = ConfigBuilder::new()
+///     .option(ResolverOption::Timeout(0))
+///     .option(ResolverOption::Rotate)
+///     .dialect(Dialect::Musl)
+///     .build()
+///     .unwrap_err();
+/// assert_eq!(errors.len(), 2);
+/// assert_eq!(errors[0].to_string(), "timeout:0 is not between 1 and 60");
+/// assert_eq!(errors[1], ValidationError::Unsupported("option rotate".into(), Dialect::Musl));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+    dialect: Option<Dialect>,
+}
+
+impl ConfigBuilder {
+    /// Create a builder for a default configuration.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a nameserver.
+    pub fn nameserver<T: Into<ScopedIp>>(mut self, ip: T) -> Self {
+        self.config.nameservers.push(ip.into());
+        self
+    }
+
+    /// Set the `domain`, replacing the search list.
+    pub fn domain<S: Into<String>>(mut self, domain: S) -> Self {
+        self.config.set_domain(domain.into());
+        self
+    }
+
+    /// Set the search list, replacing the `domain`.
+    pub fn search<I, S>(mut self, domains: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.config
+            .set_search(domains.into_iter().map(Into::into).collect());
+        self
+    }
+
+    /// Add a network to the sortlist.
+    pub fn sortlist(mut self, network: Network) -> Self {
+        self.config.sortlist.push(network);
+        self
+    }
+
+    /// Apply an option, after the previous ones.
+    pub fn option(mut self, option: ResolverOption) -> Self {
+        self.config.apply_option(option);
+        self
+    }
+
+    /// Add a database to `lookup`. **(openbsd-only)**
+    pub fn lookup(mut self, database: Lookup) -> Self {
+        self.config.lookup.push(database);
+        self
+    }
+
+    /// Add a protocol family to `family`. **(openbsd-only)**
+    pub fn family(mut self, family: Family) -> Self {
+        self.config.family.push(family);
+        self
+    }
+
+    /// Check the configuration against the limits of `dialect` too.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Return the configuration, or every reason why it is invalid.
+    pub fn build(self) -> Result<Config, Vec<ValidationError>> {
+        self.config.validate(self.dialect)?;
+        Ok(self.config)
+    }
+}
+
+impl Config {
+    /// Check the configuration, and the limits of `dialect` if given, and
+    /// return every reason why it is invalid.
+    ///
+    /// Whatever the dialect, there must be at most 3 nameservers, the
+    /// domains must be valid, the search list must not be empty, and
+    /// `ndots`, `timeout` and `attempts` must not be 0.
+    pub fn validate(&self, dialect: Option<Dialect>) -> Result<(), Vec<ValidationError>> {
+        use ValidationError::*;
+        let mut errors = Vec::new();
+
+        if self.nameservers.len() > NAMESERVER_LIMIT {
+            errors.push(TooManyNameservers {
+                count: self.nameservers.len(),
+                max: NAMESERVER_LIMIT,
+            });
+        }
+
+        if let Some(domain) = &self.domain {
+            if !is_valid_domain(domain) {
+                errors.push(InvalidDomain(domain.clone()));
+            }
+        }
+        if let (LastSearch::Search, Some(search)) = (self.last_search, &self.search) {
+            if search.is_empty() {
+                errors.push(EmptySearch);
+            }
+            for domain in search.iter().filter(|d| !is_valid_domain(d)) {
+                errors.push(InvalidDomain(domain.clone()));
+            }
+            if let Some(max) = dialect.and_then(Dialect::max_search) {
+                if search.len() > max {
+                    errors.push(TooManySearchDomains {
+                        count: search.len(),
+                        max,
+                    });
+                }
+            }
+        }
+
+        let (ndots, timeout, attempts) =
+            dialect.map_or((u32::MAX, u32::MAX, u32::MAX), Dialect::max_values);
+        for (option, value, max) in [
+            ("ndots", self.ndots, ndots),
+            ("timeout", self.timeout, timeout),
+            ("attempts", self.attempts, attempts),
+        ] {
+            if value < 1 || value > max {
+                errors.push(OutOfRange {
+                    option,
+                    value,
+                    min: 1,
+                    max,
+                });
+            }
+        }
+
+        match dialect {
+            Some(Dialect::OpenBsd) | None => {}
+            Some(dialect) => {
+                if !self.lookup.is_empty() {
+                    errors.push(Unsupported("lookup".into(), dialect));
+                }
+                if !self.family.is_empty() {
+                    errors.push(Unsupported("family".into(), dialect));
+                }
+            }
+        }
+        if dialect == Some(Dialect::Musl) {
+            for option in set_options(self) {
+                if !matches!(
+                    option,
+                    ResolverOption::Ndots(_)
+                        | ResolverOption::Timeout(_)
+                        | ResolverOption::Attempts(_)
+                ) {
+                    errors.push(Unsupported(
+                        format!("option {}", option.name()),
+                        Dialect::Musl,
+                    ));
+                }
+            }
+            for (name, _) in &self.unknown_options {
+                errors.push(Unsupported(format!("option {name}"), Dialect::Musl));
+            }
+            if !self.sortlist.is_empty() {
+                errors.push(Unsupported("sortlist".into(), Dialect::Musl));
+            }
+            for (keyword, _) in &self.unknown_directives {
+                errors.push(Unsupported(keyword.clone(), Dialect::Musl));
+            }
+        }
+
+        match errors.is_empty() {
+            true => Ok(()),
+            false => Err(errors),
+        }
+    }
+}
+
+/// Whether `domain` can be written in a resolv.conf file and looked up
+fn is_valid_domain(domain: &str) -> bool {
+    let name = domain.strip_suffix('.').unwrap_or(domain);
+    !domain.is_empty()
+        && name.len() <= MAX_DOMAIN_LENGTH
+        && !domain
+            .chars()
+            .any(|c| c.is_whitespace() || c == '#' || c == ';')
+}
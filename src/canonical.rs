@@ -0,0 +1,138 @@
+use std::net::Ipv4Addr;
+
+use crate::options::set_options;
+use crate::{Config, LastSearch, ResolverOption, ScopedIp};
+
+impl Config {
+    /// Rewrite the configuration in a canonical form, which a resolver uses
+    /// the same way:
+    ///
+    /// 1. IPv4-mapped IPv6 nameservers become IPv4 nameservers,
+    /// 2. duplicate nameservers and sortlist networks are removed, keeping
+    ///    the first one,
+    /// 3. the `domain` and the domains of `search` are lowercased and lose
+    ///    their trailing dot, and duplicate search domains are removed,
+    /// 4. the `domain` shadowed by a later `search`, or the `search`
+    ///    shadowed by a later `domain`, is removed, and a remaining
+    ///    `domain` becomes a `search` of that single domain,
+    /// 5. the options are listed once each, an unknown option keeping its
+    ///    last value.
+    ///
+    /// The number of nameservers and search domains is not limited; see
+    /// [`glibc_normalize`](#method.glibc_normalize) for that.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// # fn main() {
+    /// let mut config = Config::parse("\
+    /// nameserver ::ffff:10.0.0.1
+    /// nameserver 10.0.0.1
+    /// domain example.com
+    /// search Example.COM. corp.example.com
+    /// ").unwrap();
+    /// config.canonicalize();
+    /// assert_eq!(config.to_string(), "nameserver 10.0.0.1\nsearch example.com corp.example.com\n");
+    /// # }
+    /// ```
+    pub fn canonicalize(&mut self) {
+        let mut nameservers: Vec<ScopedIp> = Vec::with_capacity(self.nameservers.len());
+        for ip in self.nameservers.drain(..) {
+            let ip = unmap(ip);
+            if !nameservers.contains(&ip) {
+                nameservers.push(ip);
+            }
+        }
+        self.nameservers = nameservers;
+
+        let mut sortlist = Vec::with_capacity(self.sortlist.len());
+        for network in self.sortlist.drain(..) {
+            if !sortlist.contains(&network) {
+                sortlist.push(network);
+            }
+        }
+        self.sortlist = sortlist;
+
+        match self.last_search {
+            LastSearch::Search => self.domain = None,
+            LastSearch::Domain => {
+                self.search = self.domain.take().map(|domain| vec![domain]);
+                self.last_search = LastSearch::Search;
+            }
+            LastSearch::None => {}
+        }
+        self.domain = self.domain.take().map(|domain| canonical_domain(&domain));
+        self.search = self.search.take().map(|search| {
+            let mut domains: Vec<String> = Vec::with_capacity(search.len());
+            for domain in search {
+                let domain = canonical_domain(&domain);
+                if !domains.contains(&domain) {
+                    domains.push(domain);
+                }
+            }
+            domains
+        });
+
+        let mut unknown: Vec<(String, Option<String>)> = Vec::new();
+        for (name, value) in self.unknown_options.drain(..) {
+            match unknown.iter_mut().find(|(n, _)| *n == name) {
+                Some(option) => option.1 = value,
+                None => unknown.push((name, value)),
+            }
+        }
+        self.unknown_options = unknown;
+        let mut options = set_options(self);
+        options.extend(
+            self.unknown_options
+                .iter()
+                .map(|(name, value)| ResolverOption::Unknown(name.clone(), value.clone())),
+        );
+        self.options = options;
+    }
+
+    /// Return whether both configurations are the same once
+    /// [canonicalized](#method.canonicalize).
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// # fn main() {
+    /// let a = Config::parse("nameserver 10.0.0.1\nsearch example.com\noptions ndots:2").unwrap();
+    /// let b = Config::parse("domain old.example.com\nnameserver 10.0.0.1\nnameserver 10.0.0.1\n\
+    ///                        search EXAMPLE.com.\noptions ndots:3\noptions ndots:2").unwrap();
+    /// assert_ne!(a, b);
+    /// assert!(a.semantically_eq(&b));
+    /// # }
+    /// ```
+    pub fn semantically_eq(&self, other: &Self) -> bool {
+        let mut this = self.clone();
+        let mut other = other.clone();
+        this.canonicalize();
+        other.canonicalize();
+        this == other
+    }
+}
+
+/// Turn an IPv4-mapped IPv6 address into an IPv4 address
+fn unmap(ip: ScopedIp) -> ScopedIp {
+    match ip {
+        ScopedIp::V6(v6, scope) => match v6.segments() {
+            [0, 0, 0, 0, 0, 0xffff, high, low] => {
+                let [a, b] = high.to_be_bytes();
+                let [c, d] = low.to_be_bytes();
+                ScopedIp::V4(Ipv4Addr::new(a, b, c, d))
+            }
+            _ => ScopedIp::V6(v6, scope),
+        },
+        ip => ip,
+    }
+}
+
+/// Lowercase a domain and remove its trailing dot, except for the root
+fn canonical_domain(domain: &str) -> String {
+    let domain = match domain.strip_suffix('.') {
+        Some(stripped) if !stripped.is_empty() => stripped,
+        _ => domain,
+    };
+    domain.to_ascii_lowercase()
+}
@@ -0,0 +1,284 @@
+use std::collections::BTreeMap;
+use std::str::{FromStr, SplitWhitespace};
+
+use crate::grammar::{self, Visitor};
+use crate::options::set_option;
+use crate::{Config, Family, LastSearch, Lookup, Network, ParseError, ResolverOption, ScopedIp};
+
+/// A configuration borrowing its strings from the parsed buffer.
+///
+/// Parsing into a `ConfigRef` allocates nothing when the buffer is valid:
+/// the domains are slices of the buffer, and lists are iterators that scan
+/// the buffer again when called. This makes it much cheaper than [`Config`]
+/// to inspect many files once; use [`to_owned`](#method.to_owned) to keep
+/// the configuration around.
+///
+/// The options are public fields with the same meaning and defaults as in
+/// [`Config`].
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{Config, ConfigRef};
+/// # fn main() {
+/// let buf = b"nameserver 8.8.8.8\nsearch example.com sub.example.com\noptions ndots:2\n";
+/// let config = ConfigRef::parse(buf).unwrap();
+/// assert_eq!(config.search().unwrap().collect::<Vec<_>>(), ["example.com", "sub.example.com"]);
+/// assert_eq!(config.nameservers().count(), 1);
+/// assert_eq!(config.ndots, 2);
+/// assert_eq!(config.to_owned(), Config::parse(buf).unwrap());
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ConfigRef<'a> {
+    bytes: &'a [u8],
+    last_search: LastSearch,
+    domain: Option<&'a str>,
+    /// The arguments of the last `search` directive
+    search: Option<&'a str>,
+    /// The arguments of the last `sortlist` directive
+    sortlist: Option<&'a str>,
+    /// Enable DNS resolve debugging
+    pub debug: bool,
+    /// Number of dots in name to try absolute resolving first (default 1)
+    pub ndots: u32,
+    /// Dns query timeout (default 5 seconds)
+    pub timeout: u32,
+    /// Number of attempts to resolve name if server is inaccesible (default 2)
+    pub attempts: u32,
+    /// Round-robin selection of servers (default false)
+    pub rotate: bool,
+    /// Don't check names for validity (default false)
+    pub no_check_names: bool,
+    /// Try AAAA query before A
+    pub inet6: bool,
+    /// Use reverse lookup of ipv6 using bit-label format described instead
+    /// of nibble format
+    pub ip6_bytestring: bool,
+    /// Do ipv6 reverse lookups in ip6.int zone instead of ip6.arpa
+    /// (default false)
+    pub ip6_dotint: bool,
+    /// Enable dns extensions described in RFC 2671
+    pub edns0: bool,
+    /// Don't make ipv4 and ipv6 requests simultaneously
+    pub single_request: bool,
+    /// Use same socket for the A and AAAA requests
+    pub single_request_reopen: bool,
+    /// Don't resolve unqualified name as top level domain
+    pub no_tld_query: bool,
+    /// Force using TCP for DNS resolution
+    pub use_vc: bool,
+    /// Disable the automatic reloading of a changed configuration file
+    pub no_reload: bool,
+    /// Optionally send the AD (authenticated data) bit in queries
+    pub trust_ad: bool,
+    /// Suppress AAAA queries made by the stub resolver
+    pub no_aaaa: bool,
+}
+
+impl<'a> ConfigRef<'a> {
+    /// Parse a buffer, failing on the first error like [`Config::parse`].
+    pub fn parse<T: AsRef<[u8]> + ?Sized>(buf: &'a T) -> Result<Self, ParseError> {
+        let (config, errors) = Self::parse_with_errors(buf.as_ref());
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(config),
+        }
+    }
+
+    /// Parse a buffer and return a best-effort configuration along with any
+    /// errors, like [`Config::parse_with_errors`].
+    pub fn parse_with_errors(bytes: &'a [u8]) -> (Self, Vec<ParseError>) {
+        let mut visitor = RefVisitor {
+            config: Self::empty(bytes),
+            errors: Vec::new(),
+        };
+        grammar::visit(bytes, &mut visitor);
+        (visitor.config, visitor.errors)
+    }
+
+    fn empty(bytes: &'a [u8]) -> Self {
+        let defaults = Config::new();
+        Self {
+            bytes,
+            last_search: LastSearch::None,
+            domain: None,
+            search: None,
+            sortlist: None,
+            debug: defaults.debug,
+            ndots: defaults.ndots,
+            timeout: defaults.timeout,
+            attempts: defaults.attempts,
+            rotate: defaults.rotate,
+            no_check_names: defaults.no_check_names,
+            inet6: defaults.inet6,
+            ip6_bytestring: defaults.ip6_bytestring,
+            ip6_dotint: defaults.ip6_dotint,
+            edns0: defaults.edns0,
+            single_request: defaults.single_request,
+            single_request_reopen: defaults.single_request_reopen,
+            no_tld_query: defaults.no_tld_query,
+            use_vc: defaults.use_vc,
+            no_reload: defaults.no_reload,
+            trust_ad: defaults.trust_ad,
+            no_aaaa: defaults.no_aaaa,
+        }
+    }
+
+    /// Iterate over the nameservers.
+    pub fn nameservers(&self) -> impl Iterator<Item = ScopedIp> + 'a {
+        self.directive("nameserver")
+            .filter_map(|rest| ScopedIp::from_str(rest.split_whitespace().next()?).ok())
+    }
+
+    /// Return the domain declared in the last "domain" directive.
+    pub fn domain(&self) -> Option<&'a str> {
+        self.domain
+    }
+
+    /// Iterate over the domains declared in the last "search" directive.
+    pub fn search(&self) -> Option<SplitWhitespace<'a>> {
+        self.search.map(str::split_whitespace)
+    }
+
+    /// Iterate over the suffixes declared in the last "domain" or "search"
+    /// directive, like [`Config::get_last_search_or_domain`].
+    pub fn last_search_or_domain(&self) -> impl Iterator<Item = &'a str> {
+        let (search, domain) = match self.last_search {
+            LastSearch::Search => (self.search(), None),
+            LastSearch::Domain => (None, self.domain),
+            LastSearch::None => (None, None),
+        };
+        search.into_iter().flatten().chain(domain)
+    }
+
+    /// Iterate over the networks of the last "sortlist" directive.
+    pub fn sortlist(&self) -> impl Iterator<Item = Network> + 'a {
+        self.sortlist
+            .unwrap_or("")
+            .split_whitespace()
+            .filter_map(|pair| Network::from_str(pair).ok())
+    }
+
+    /// Iterate over the databases of the "lookup" directives.
+    /// **(openbsd-only)**
+    pub fn lookup(&self) -> impl Iterator<Item = &'a str> + 'a {
+        self.directive("lookup").flat_map(str::split_whitespace)
+    }
+
+    /// Iterate over the protocol families of the "family" directives.
+    /// **(openbsd-only)**
+    pub fn family(&self) -> impl Iterator<Item = Family> + 'a {
+        self.directive("family")
+            .flat_map(str::split_whitespace)
+            .filter_map(|word| match word {
+                "inet4" => Some(Family::Inet4),
+                "inet6" => Some(Family::Inet6),
+                _ => None,
+            })
+    }
+
+    /// Iterate over the valid options, in source order, like
+    /// [`Config::options`].
+    pub fn options(&self) -> impl Iterator<Item = ResolverOption> + 'a {
+        self.directive("options")
+            .flat_map(str::split_whitespace)
+            .filter_map(|option| option.parse().ok())
+    }
+
+    /// Iterate over the options this crate does not know, with their value
+    /// if any.
+    pub fn unknown_options(&self) -> impl Iterator<Item = (&'a str, Option<&'a str>)> + 'a {
+        self.directive("options")
+            .flat_map(str::split_whitespace)
+            .map(grammar::split_option)
+            .filter(|(name, value)| matches!(ResolverOption::decode(name, *value, 0), Ok(None)))
+    }
+
+    /// Iterate over the directives this crate does not know, with their
+    /// arguments.
+    pub fn unknown_directives(&self) -> impl Iterator<Item = (&'a str, &'a str)> + 'a {
+        grammar::directives(self.bytes).filter(|(keyword, _)| !grammar::KEYWORDS.contains(keyword))
+    }
+
+    /// Copy the configuration into an owned [`Config`].
+    pub fn to_owned(&self) -> Config {
+        Config {
+            nameservers: self.nameservers().collect(),
+            sortlist: self.sortlist().collect(),
+            lookup: self.lookup().map(Lookup::from_word).collect(),
+            family: self.family().collect(),
+            debug: self.debug,
+            ndots: self.ndots,
+            timeout: self.timeout,
+            attempts: self.attempts,
+            rotate: self.rotate,
+            no_check_names: self.no_check_names,
+            inet6: self.inet6,
+            ip6_bytestring: self.ip6_bytestring,
+            ip6_dotint: self.ip6_dotint,
+            edns0: self.edns0,
+            single_request: self.single_request,
+            single_request_reopen: self.single_request_reopen,
+            no_tld_query: self.no_tld_query,
+            use_vc: self.use_vc,
+            no_reload: self.no_reload,
+            trust_ad: self.trust_ad,
+            no_aaaa: self.no_aaaa,
+            last_search: self.last_search,
+            domain: self.domain.map(str::to_owned),
+            search: self.search().map(|s| s.map(str::to_owned).collect()),
+            options: self.options().collect(),
+            unknown_options: self
+                .unknown_options()
+                .map(|(name, value)| (name.to_owned(), value.map(str::to_owned)))
+                .collect(),
+            unknown_directives: self
+                .unknown_directives()
+                .map(|(keyword, args)| {
+                    let args = args.split_whitespace().map(str::to_owned).collect();
+                    (keyword.to_owned(), args)
+                })
+                .collect(),
+            extensions: BTreeMap::new(),
+            source_map: None,
+        }
+    }
+
+    /// Iterate over the arguments of every `keyword` directive
+    fn directive(&self, keyword: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        grammar::directives(self.bytes).filter_map(move |(k, rest)| match k == keyword {
+            true => Some(rest),
+            false => None,
+        })
+    }
+}
+
+/// Fills a [`ConfigRef`] and collects the errors
+struct RefVisitor<'a> {
+    config: ConfigRef<'a>,
+    errors: Vec<ParseError>,
+}
+
+impl<'a> Visitor<'a> for RefVisitor<'a> {
+    fn domain(&mut self, _: usize, domain: &'a str) {
+        self.config.domain = Some(domain);
+        self.config.last_search = LastSearch::Domain;
+    }
+
+    fn search(&mut self, _: usize, domains: &'a str) {
+        self.config.search = Some(domains);
+        self.config.last_search = LastSearch::Search;
+    }
+
+    fn sortlist(&mut self, _: usize, networks: &'a str) {
+        self.config.sortlist = Some(networks);
+    }
+
+    fn option(&mut self, _: usize, option: ResolverOption, _: &'a str) {
+        set_option!(&mut self.config, &option);
+    }
+
+    fn error(&mut self, error: ParseError) {
+        self.errors.push(error);
+    }
+}
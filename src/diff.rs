@@ -0,0 +1,459 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::options::set_options;
+use crate::{Config, Family, LastSearch, Lookup, Network, ResolverOption, ScopedIp};
+
+/// A difference between two configurations, as found by [`Config::diff`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ConfigChange {
+    /// A nameserver was added, at the given index of the new list
+    NameserverAdded(usize, ScopedIp),
+    /// A nameserver was removed
+    NameserverRemoved(ScopedIp),
+    /// The nameservers present in both configurations are in a different
+    /// order, given here
+    NameserversReordered(Vec<ScopedIp>),
+    /// The `domain` changed
+    DomainChanged {
+        /// The previous domain
+        old: Option<String>,
+        /// The new domain
+        new: Option<String>,
+    },
+    /// The search list, made of the last `domain` or `search` directive,
+    /// changed
+    SearchChanged {
+        /// The previous search list
+        old: Vec<String>,
+        /// The new search list
+        new: Vec<String>,
+    },
+    /// An option was set, unset or changed its value. `None` stands for an
+    /// option left to its default. `ip6-dotint` and `no-ip6-dotint` are the
+    /// same option.
+    OptionChanged {
+        /// The previous option
+        old: Option<ResolverOption>,
+        /// The new option
+        new: Option<ResolverOption>,
+    },
+    /// The `sortlist` changed
+    SortlistChanged {
+        /// The previous networks
+        old: Vec<Network>,
+        /// The new networks
+        new: Vec<Network>,
+    },
+    /// The `lookup` databases changed
+    LookupChanged {
+        /// The previous databases
+        old: Vec<Lookup>,
+        /// The new databases
+        new: Vec<Lookup>,
+    },
+    /// The `family` list changed
+    FamilyChanged {
+        /// The previous families
+        old: Vec<Family>,
+        /// The new families
+        new: Vec<Family>,
+    },
+    /// The directives this crate does not know changed
+    UnknownDirectivesChanged {
+        /// The previous directives
+        old: Vec<(String, Vec<String>)>,
+        /// The new directives
+        new: Vec<(String, Vec<String>)>,
+    },
+    /// A value stored by the handlers of a [`Parser`](crate::Parser) was
+    /// added, removed or changed
+    ExtensionChanged {
+        /// The name of the value
+        name: String,
+        /// The previous value
+        old: Option<String>,
+        /// The new value
+        new: Option<String>,
+    },
+}
+
+/// The changes between two configurations, as returned by
+/// [`Config::diff`].
+///
+/// Only the values a resolver uses are compared: the order of the options,
+/// an option set to its default value, and whether `domain` or `search` is
+/// written last when they agree, are not changes. `Display` writes one
+/// change per line.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::Config;
+/// # fn main() {
+/// let old = Config::parse("nameserver 10.0.0.1\nnameserver 10.0.0.2\noptions ndots:2").unwrap();
+/// let new = Config::parse("nameserver 10.0.0.2\nnameserver 10.0.0.3\noptions ndots:5 rotate").unwrap();
+///
+/// let diff = old.diff(&new);
+/// assert_eq!(diff.to_string(), "\
+/// nameserver 10.0.0.1 removed
+/// nameserver 10.0.0.3 added
+/// option ndots:2 changed to ndots:5
+/// option rotate added
+/// ");
+///
+/// let mut patched = old.clone();
+/// diff.apply(&mut patched);
+/// assert_eq!(patched, new);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConfigDiff {
+    /// The changes, in the order they are applied
+    pub changes: Vec<ConfigChange>,
+}
+
+impl Config {
+    /// Compute the changes turning this configuration into `other`.
+    pub fn diff(&self, other: &Self) -> ConfigDiff {
+        use ConfigChange::*;
+        let mut changes = Vec::new();
+
+        let common = |list: &[ScopedIp], other: &[ScopedIp]| -> Vec<ScopedIp> {
+            list.iter()
+                .filter(|ip| other.contains(ip))
+                .cloned()
+                .collect()
+        };
+        for ip in &self.nameservers {
+            if !other.nameservers.contains(ip) {
+                changes.push(NameserverRemoved(ip.clone()));
+            }
+        }
+        let order = common(&other.nameservers, &self.nameservers);
+        if common(&self.nameservers, &other.nameservers) != order {
+            changes.push(NameserversReordered(order));
+        }
+        for (index, ip) in other.nameservers.iter().enumerate() {
+            if !self.nameservers.contains(ip) {
+                changes.push(NameserverAdded(index, ip.clone()));
+            }
+        }
+
+        if self.domain != other.domain {
+            changes.push(DomainChanged {
+                old: self.domain.clone(),
+                new: other.domain.clone(),
+            });
+        }
+        let old = self
+            .get_last_search_or_domain()
+            .cloned()
+            .collect::<Vec<_>>();
+        let new = other
+            .get_last_search_or_domain()
+            .cloned()
+            .collect::<Vec<_>>();
+        if old != new {
+            changes.push(SearchChanged { old, new });
+        }
+
+        let old = options_by_field(self);
+        let new = options_by_field(other);
+        let fields = old
+            .keys()
+            .chain(new.keys().filter(|f| !old.contains_key(*f)));
+        for field in fields {
+            let (old, new) = (old.get(field), new.get(field));
+            if effective(old) != effective(new) {
+                changes.push(OptionChanged {
+                    old: old.cloned(),
+                    new: new.cloned(),
+                });
+            }
+        }
+
+        if self.sortlist != other.sortlist {
+            changes.push(SortlistChanged {
+                old: self.sortlist.clone(),
+                new: other.sortlist.clone(),
+            });
+        }
+        if self.lookup != other.lookup {
+            changes.push(LookupChanged {
+                old: self.lookup.clone(),
+                new: other.lookup.clone(),
+            });
+        }
+        if self.family != other.family {
+            changes.push(FamilyChanged {
+                old: self.family.clone(),
+                new: other.family.clone(),
+            });
+        }
+        if self.unknown_directives != other.unknown_directives {
+            changes.push(UnknownDirectivesChanged {
+                old: self.unknown_directives.clone(),
+                new: other.unknown_directives.clone(),
+            });
+        }
+
+        let names = self.extensions.keys().chain(
+            other
+                .extensions
+                .keys()
+                .filter(|name| !self.extensions.contains_key(*name)),
+        );
+        for name in names {
+            let (old, new) = (self.extensions.get(name), other.extensions.get(name));
+            if old != new {
+                changes.push(ExtensionChanged {
+                    name: name.clone(),
+                    old: old.cloned(),
+                    new: new.cloned(),
+                });
+            }
+        }
+        ConfigDiff { changes }
+    }
+}
+
+impl ConfigDiff {
+    /// Return whether the configurations are the same.
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+
+    /// Apply the changes to `config`.
+    ///
+    /// Applied to the configuration it was computed from, the diff gives a
+    /// configuration equal to the other one, except when `domain` and
+    /// `search` list the same single domain: which of them is written
+    /// last may differ, which a resolver does not see.
+    pub fn apply(&self, config: &mut Config) {
+        use ConfigChange::*;
+        for change in &self.changes {
+            match change {
+                NameserverRemoved(ip) => config.nameservers.retain(|ns| ns != ip),
+                NameserversReordered(order) => {
+                    let mut rest = config
+                        .nameservers
+                        .iter()
+                        .filter(|ip| !order.contains(ip))
+                        .cloned()
+                        .collect();
+                    config.nameservers = order.clone();
+                    config.nameservers.append(&mut rest);
+                }
+                NameserverAdded(index, ip) => {
+                    let index = (*index).min(config.nameservers.len());
+                    config.nameservers.insert(index, ip.clone());
+                }
+                DomainChanged { new, .. } => config.domain = new.clone(),
+                // a search list hidden by a later `domain` is not kept
+                SearchChanged { new, .. } => match &new[..] {
+                    [] => {
+                        config.search = None;
+                        config.last_search = LastSearch::None;
+                    }
+                    [domain] if config.domain.as_ref() == Some(domain) => {
+                        config.search = None;
+                        config.last_search = LastSearch::Domain;
+                    }
+                    _ => config.set_search(new.clone()),
+                },
+                OptionChanged { old, new } => {
+                    if let Some(old) = old {
+                        unset_option(config, old);
+                    }
+                    if let Some(new) = new {
+                        unset_option(config, new);
+                        config.apply_option(new.clone());
+                    }
+                }
+                SortlistChanged { new, .. } => config.sortlist = new.clone(),
+                LookupChanged { new, .. } => config.lookup = new.clone(),
+                FamilyChanged { new, .. } => config.family = new.clone(),
+                UnknownDirectivesChanged { new, .. } => config.unknown_directives = new.clone(),
+                ExtensionChanged { name, new, .. } => match new {
+                    Some(new) => {
+                        config.extensions.insert(name.clone(), new.clone());
+                    }
+                    None => {
+                        config.extensions.remove(name);
+                    }
+                },
+            }
+        }
+    }
+}
+
+impl fmt::Display for ConfigDiff {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        for change in &self.changes {
+            writeln!(fmt, "{change}")?;
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Display for ConfigChange {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        use ConfigChange::*;
+        match self {
+            NameserverAdded(_, ip) => write!(fmt, "nameserver {ip} added"),
+            NameserverRemoved(ip) => write!(fmt, "nameserver {ip} removed"),
+            NameserversReordered(order) => {
+                write!(fmt, "nameservers reordered:")?;
+                for ip in order {
+                    write!(fmt, " {ip}")?;
+                }
+                Ok(())
+            }
+            DomainChanged { old, new } => match (old, new) {
+                (None, Some(new)) => write!(fmt, "domain {new} added"),
+                (Some(old), None) => write!(fmt, "domain {old} removed"),
+                (Some(old), Some(new)) => write!(fmt, "domain {old} changed to {new}"),
+                (None, None) => Ok(()),
+            },
+            SearchChanged { old, new } => {
+                write!(
+                    fmt,
+                    "search list changed from [{}] to [{}]",
+                    old.join(" "),
+                    new.join(" ")
+                )
+            }
+            OptionChanged { old, new } => match (old, new) {
+                (None, Some(new)) => write!(fmt, "option {new} added"),
+                (Some(old), None) => write!(fmt, "option {old} removed"),
+                (Some(old), Some(new)) => write!(fmt, "option {old} changed to {new}"),
+                (None, None) => Ok(()),
+            },
+            SortlistChanged { old, new } => {
+                let words = |list: &[Network]| {
+                    list.iter()
+                        .map(Network::to_string)
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                write!(
+                    fmt,
+                    "sortlist changed from [{}] to [{}]",
+                    words(old),
+                    words(new)
+                )
+            }
+            LookupChanged { old, new } => {
+                let words = |list: &[Lookup]| {
+                    list.iter()
+                        .map(|db| match db {
+                            Lookup::File => "file",
+                            Lookup::Bind => "bind",
+                            Lookup::Extra(extra) => extra.as_str(),
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                write!(
+                    fmt,
+                    "lookup changed from [{}] to [{}]",
+                    words(old),
+                    words(new)
+                )
+            }
+            FamilyChanged { old, new } => {
+                let words = |list: &[Family]| {
+                    list.iter()
+                        .map(|family| match family {
+                            Family::Inet4 => "inet4",
+                            Family::Inet6 => "inet6",
+                        })
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                write!(
+                    fmt,
+                    "family changed from [{}] to [{}]",
+                    words(old),
+                    words(new)
+                )
+            }
+            UnknownDirectivesChanged { old, new } => {
+                let keywords = |list: &[(String, Vec<String>)]| {
+                    list.iter()
+                        .map(|(keyword, _)| keyword.as_str())
+                        .collect::<Vec<_>>()
+                        .join(" ")
+                };
+                write!(
+                    fmt,
+                    "unknown directives changed from [{}] to [{}]",
+                    keywords(old),
+                    keywords(new)
+                )
+            }
+            ExtensionChanged { name, old, new } => match (old, new) {
+                (None, Some(new)) => write!(fmt, "extension {name}={new} added"),
+                (Some(old), None) => write!(fmt, "extension {name}={old} removed"),
+                (Some(old), Some(new)) => {
+                    write!(fmt, "extension {name} changed from {old} to {new}")
+                }
+                (None, None) => Ok(()),
+            },
+        }
+    }
+}
+
+/// The options of `config` that are set, known or not, by the name of the
+/// field they set
+fn options_by_field(config: &Config) -> BTreeMap<String, ResolverOption> {
+    let mut options = BTreeMap::new();
+    for option in set_options(config) {
+        options.insert(option.field().to_owned(), option);
+    }
+    for (name, value) in &config.unknown_options {
+        let option = ResolverOption::Unknown(name.clone(), value.clone());
+        options.insert(name.clone(), option);
+    }
+    options
+}
+
+/// The option a resolver sees, `None` for an option set to its default
+fn effective(option: Option<&ResolverOption>) -> Option<&ResolverOption> {
+    use ResolverOption::*;
+    let defaults = Config::new();
+    option.filter(|option| match option {
+        Ndots(ndots) => *ndots != defaults.ndots,
+        Timeout(timeout) => *timeout != defaults.timeout,
+        Attempts(attempts) => *attempts != defaults.attempts,
+        NoIp6Dotint => defaults.ip6_dotint,
+        _ => true,
+    })
+}
+
+/// Bring the field set by `option` back to its default
+fn unset_option(config: &mut Config, option: &ResolverOption) {
+    let defaults = Config::new();
+    let field = option.field();
+    match field {
+        "debug" => config.debug = false,
+        "ndots" => config.ndots = defaults.ndots,
+        "timeout" => config.timeout = defaults.timeout,
+        "attempts" => config.attempts = defaults.attempts,
+        "rotate" => config.rotate = false,
+        "no-check-names" => config.no_check_names = false,
+        "inet6" => config.inet6 = false,
+        "ip6-bytestring" => config.ip6_bytestring = false,
+        "ip6-dotint" => config.ip6_dotint = defaults.ip6_dotint,
+        "edns0" => config.edns0 = false,
+        "single-request" => config.single_request = false,
+        "single-request-reopen" => config.single_request_reopen = false,
+        "no-tld-query" => config.no_tld_query = false,
+        "use-vc" => config.use_vc = false,
+        "no-reload" => config.no_reload = false,
+        "trust-ad" => config.trust_ad = false,
+        "no-aaaa" => config.no_aaaa = false,
+        _ => config.unknown_options.retain(|(n, _)| n != field),
+    }
+    config.options.retain(|option| option.field() != field);
+}
@@ -0,0 +1,324 @@
+use std::str::{from_utf8, FromStr, Utf8Error};
+
+use crate::{Config, Family, Lookup, Network, ParseError, Parser, ResolverOption, ScopedIp};
+
+/// A line of a resolv.conf file, split in its keyword and arguments
+pub(crate) enum Line<'a> {
+    /// A line with nothing but whitespace
+    Empty,
+    /// A comment line, with the text after the comment character
+    Comment(&'a [u8]),
+    /// A directive, with the text after the keyword, up to the comment if
+    /// any
+    Directive(&'a str, &'a str),
+}
+
+/// Split a line of a resolv.conf file.
+pub(crate) fn tokenize(line: &[u8]) -> Result<Line<'_>, Utf8Error> {
+    for (i, &c) in line.iter().enumerate() {
+        if c != b'\t' && c != b' ' {
+            if c == b';' || c == b'#' {
+                // comments may contain invalid UTF-8
+                return Ok(Line::Comment(&line[i + 1..]));
+            }
+            break;
+        }
+    }
+
+    // ignore everything after ';' or '#'
+    let text = from_utf8(line)?.split([';', '#']).next().unwrap_or("");
+    let text = text.trim_start();
+    let end = text.find(char::is_whitespace).unwrap_or(text.len());
+    Ok(match text.split_at(end) {
+        ("", _) => Line::Empty,
+        (keyword, rest) => Line::Directive(keyword, rest),
+    })
+}
+
+/// The keywords of the directives known to [`visit_line`]
+pub(crate) const KEYWORDS: &[&str] = &[
+    "nameserver",
+    "domain",
+    "search",
+    "sortlist",
+    "options",
+    "lookup",
+    "family",
+];
+
+/// Split an option in its name and value
+pub(crate) fn split_option(option: &str) -> (&str, Option<&str>) {
+    let mut iter = option.splitn(2, ':');
+    (iter.next().unwrap_or(""), iter.next())
+}
+
+/// Iterate over the directives of a buffer, skipping the lines that can not
+/// be decoded.
+pub(crate) fn directives(bytes: &[u8]) -> impl Iterator<Item = (&str, &str)> {
+    bytes
+        .split(|&c| c == b'\n')
+        .filter_map(|line| match tokenize(line) {
+            Ok(Line::Directive(keyword, rest)) => Some((keyword, rest)),
+            _ => None,
+        })
+}
+
+/// Receives what is found in a resolv.conf file, line by line, from
+/// [`visit`].
+///
+/// Every method does nothing by default. Line numbers start at 0, like in
+/// [`ParseError`]. Unknown directives and options are reported as errors
+/// unless [`unknown_directive`](#method.unknown_directive) or
+/// [`unknown_option`](#method.unknown_option) handle them.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{visit, ScopedIp, Visitor};
+///
+/// #[derive(Default)]
+/// struct Counter {
+///     nameservers: usize,
+///     comments: usize,
+/// }
+///
+/// impl<'a> Visitor<'a> for Counter {
+///     fn nameserver(&mut self, _: usize, _: ScopedIp, _: &'a str) {
+///         self.nameservers += 1;
+///     }
+///
+///     fn comment(&mut self, _: usize, _: &'a [u8]) {
+///         self.comments += 1;
+///     }
+/// }
+///
+/// # fn main() {
+/// let mut counter = Counter::default();
+/// visit(b"# local\nnameserver 127.0.0.1\nnameserver ::1\n", &mut counter);
+/// assert_eq!((counter.nameservers, counter.comments), (2, 1));
+/// # }
+/// ```
+pub trait Visitor<'a> {
+    /// A comment line, with the text after the comment character
+    fn comment(&mut self, _lineno: usize, _text: &'a [u8]) {}
+    /// A `nameserver` directive, with the text of the address
+    fn nameserver(&mut self, _lineno: usize, _ip: ScopedIp, _text: &'a str) {}
+    /// A `domain` directive
+    fn domain(&mut self, _lineno: usize, _domain: &'a str) {}
+    /// A `search` directive, with its whitespace-separated domains
+    fn search(&mut self, _lineno: usize, _domains: &'a str) {}
+    /// A `sortlist` directive, with its whitespace-separated networks. It is
+    /// followed by a call to `network` for each valid network.
+    fn sortlist(&mut self, _lineno: usize, _networks: &'a str) {}
+    /// A network of the last `sortlist` directive, with its text
+    fn network(&mut self, _lineno: usize, _network: Network, _text: &'a str) {}
+    /// A built-in option of an `options` directive, with its text
+    fn option(&mut self, _lineno: usize, _option: ResolverOption, _text: &'a str) {}
+    /// A database of a `lookup` directive
+    fn lookup(&mut self, _lineno: usize, _database: &'a str) {}
+    /// A protocol family of a `family` directive, with its text
+    fn family(&mut self, _lineno: usize, _family: Family, _text: &'a str) {}
+    /// A directive this crate does not know, with its arguments. Return
+    /// `true` if it was handled, `false` to report an `InvalidDirective`
+    /// error.
+    fn unknown_directive(&mut self, _lineno: usize, _keyword: &'a str, _args: &'a str) -> bool {
+        false
+    }
+    /// An option this crate does not know, with its value if any. Return
+    /// `true` if it was handled, `false` to report an `InvalidOption` error.
+    fn unknown_option(&mut self, _lineno: usize, _name: &'a str, _value: Option<&'a str>) -> bool {
+        false
+    }
+    /// An error
+    fn error(&mut self, _error: ParseError) {}
+}
+
+/// Parse every line of `bytes` into `visitor`.
+pub fn visit<'a, V: Visitor<'a>>(bytes: &'a [u8], visitor: &mut V) {
+    for (lineno, line) in bytes.split(|&x| x == b'\n').enumerate() {
+        visit_line(lineno, line, visitor);
+    }
+}
+
+/// Parse a single line into `visitor`.
+pub(crate) fn visit_line<'a, V: Visitor<'a>>(lineno: usize, line: &'a [u8], visitor: &mut V) {
+    use ParseError::*;
+    let (keyword, rest) = match tokenize(line) {
+        Ok(Line::Directive(keyword, rest)) => (keyword, rest),
+        Ok(Line::Comment(text)) => return visitor.comment(lineno, text),
+        Ok(Line::Empty) => return,
+        Err(e) => return visitor.error(InvalidUtf8(lineno, e)),
+    };
+
+    let mut words = rest.split_whitespace();
+    match keyword {
+        "nameserver" => {
+            let srv = match words.next() {
+                Some(srv) => srv,
+                None => return visitor.error(InvalidValue(lineno)),
+            };
+
+            match ScopedIp::from_str(srv) {
+                Ok(addr) => visitor.nameserver(lineno, addr, srv),
+                Err(e) => visitor.error(InvalidIp(lineno, e)),
+            }
+
+            if words.next().is_some() {
+                visitor.error(ExtraData(lineno));
+            }
+        }
+        "domain" => {
+            let domain = match words.next() {
+                Some(domain) => domain,
+                None => return visitor.error(InvalidValue(lineno)),
+            };
+
+            visitor.domain(lineno, domain);
+            if words.next().is_some() {
+                visitor.error(ExtraData(lineno));
+            }
+        }
+        "search" => visitor.search(lineno, rest),
+        "sortlist" => {
+            visitor.sortlist(lineno, rest);
+            for pair in words {
+                match Network::from_str(pair) {
+                    Ok(network) => visitor.network(lineno, network, pair),
+                    Err(e) => visitor.error(InvalidIp(lineno, e)),
+                }
+            }
+        }
+        "options" => {
+            for pair in words {
+                let (key, value) = split_option(pair);
+                match ResolverOption::decode(key, value, lineno) {
+                    Ok(Some(option)) => visitor.option(lineno, option, pair),
+                    Ok(None) => {
+                        if !visitor.unknown_option(lineno, key, value) {
+                            visitor.error(InvalidOption(lineno));
+                        }
+                    }
+                    Err(e) => visitor.error(e),
+                }
+            }
+        }
+        "lookup" => {
+            for word in words {
+                visitor.lookup(lineno, word);
+            }
+        }
+        "family" => {
+            for word in words {
+                let family = match word {
+                    "inet4" => Family::Inet4,
+                    "inet6" => Family::Inet6,
+                    _ => {
+                        visitor.error(InvalidValue(lineno));
+                        continue;
+                    }
+                };
+                visitor.family(lineno, family, word);
+            }
+        }
+        _ => {
+            if !visitor.unknown_directive(lineno, keyword, rest) {
+                visitor.error(InvalidDirective(lineno));
+            }
+        }
+    }
+}
+
+/// Fills a [`Config`] and collects the errors
+pub(crate) struct ConfigVisitor<'c> {
+    config: &'c mut Config,
+    errors: &'c mut Vec<ParseError>,
+    parser: Option<&'c Parser>,
+}
+
+impl<'c> ConfigVisitor<'c> {
+    pub(crate) fn new(config: &'c mut Config, errors: &'c mut Vec<ParseError>) -> Self {
+        Self {
+            config,
+            errors,
+            parser: None,
+        }
+    }
+
+    /// Handle unknown directives and options with the handlers of `parser`
+    pub(crate) fn with_parser(mut self, parser: &'c Parser) -> Self {
+        self.parser = Some(parser);
+        self
+    }
+}
+
+impl<'a> Visitor<'a> for ConfigVisitor<'_> {
+    fn nameserver(&mut self, _: usize, ip: ScopedIp, _: &'a str) {
+        self.config.nameservers.push(ip);
+    }
+
+    fn domain(&mut self, _: usize, domain: &'a str) {
+        self.config.set_domain(domain.to_owned());
+    }
+
+    fn search(&mut self, _: usize, domains: &'a str) {
+        self.config
+            .set_search(domains.split_whitespace().map(|x| x.to_string()).collect());
+    }
+
+    fn sortlist(&mut self, _: usize, _: &'a str) {
+        self.config.sortlist.clear();
+    }
+
+    fn network(&mut self, _: usize, network: Network, _: &'a str) {
+        self.config.sortlist.push(network);
+    }
+
+    fn option(&mut self, _: usize, option: ResolverOption, _: &'a str) {
+        self.config.apply_option(option);
+    }
+
+    fn lookup(&mut self, _: usize, database: &'a str) {
+        self.config.lookup.push(Lookup::from_word(database));
+    }
+
+    fn family(&mut self, _: usize, family: Family, _: &'a str) {
+        self.config.family.push(family);
+    }
+
+    fn unknown_directive(&mut self, lineno: usize, keyword: &'a str, args: &'a str) -> bool {
+        let handler = match self.parser.and_then(|p| p.directive_handler(keyword)) {
+            Some(handler) => handler,
+            None => {
+                let args = args.split_whitespace().map(str::to_owned).collect();
+                self.config
+                    .unknown_directives
+                    .push((keyword.to_owned(), args));
+                return false;
+            }
+        };
+        if let Err(e) = handler(lineno, args, &mut self.config.extensions) {
+            self.errors.push(e);
+        }
+        true
+    }
+
+    fn unknown_option(&mut self, lineno: usize, name: &'a str, value: Option<&'a str>) -> bool {
+        let handler = match self.parser.and_then(|p| p.option_handler(name)) {
+            Some(handler) => handler,
+            None => {
+                let unknown = ResolverOption::Unknown(name.to_owned(), value.map(str::to_owned));
+                self.config.apply_option(unknown);
+                return false;
+            }
+        };
+        // The handler stores the option in `extensions`, like a handled
+        // directive, so it is not one of `options()`
+        if let Err(e) = handler(lineno, value, &mut self.config.extensions) {
+            self.errors.push(e);
+        }
+        true
+    }
+
+    fn error(&mut self, error: ParseError) {
+        self.errors.push(error);
+    }
+}
@@ -69,37 +69,115 @@
 //! ## Parsing a file
 //!
 //! ```rust
-//! use std::io::Read;
-//! use std::fs::File;
-//!
 //! extern crate resolv_conf;
 //!
 //! fn main() {
-//!     // Read the file
-//!     let mut buf = Vec::with_capacity(4096);
-//!     let mut f = File::open("/etc/resolv.conf").unwrap();
-//!     f.read_to_end(&mut buf).unwrap();
-//!
-//!     // Parse the buffer
-//!     let cfg = resolv_conf::Config::parse(&buf).unwrap();
+//!     // Read and parse the file, falling back to the local nameservers like glibc
+//!     let system = resolv_conf::Config::from_system().unwrap();
 //!
 //!     // Print the config
-//!     println!("---- Parsed /etc/resolv.conf -----\n{:#?}\n", cfg);
+//!     println!("---- Parsed {} -----\n{:#?}\n", system.path.display(), system.config);
 //! }
 //! ```
 
 #![warn(missing_debug_implementations, missing_docs, unreachable_pub)]
 #![warn(clippy::use_self)]
 
+use std::collections::BTreeMap;
 use std::fmt;
 use std::iter::Iterator;
 use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
 use std::slice::Iter;
-use std::str::{self, from_utf8, FromStr, Utf8Error};
+use std::str::{self, Utf8Error};
+
+mod atomic;
+pub use atomic::{SymlinkPolicy, WriteOptions};
+
+mod block;
+pub use block::{BlockError, ManagedBlock};
+
+mod builder;
+pub use builder::{ConfigBuilder, Dialect, ValidationError};
+
+mod canonical;
+
+mod config_ref;
+pub use config_ref::ConfigRef;
+
+mod diff;
+pub use diff::{ConfigChange, ConfigDiff};
+
+mod grammar;
+use grammar::ConfigVisitor;
+pub use grammar::{visit, Visitor};
 
 mod ip;
 pub use ip::{AddrParseError, Network, ScopedIp};
 
+mod load;
+pub use load::{Fallback, LoadError, SystemLoad, SYSTEM_RESOLV_CONF};
+
+mod managed;
+pub use managed::{ManagedBy, Ownership, OwnershipEvidence};
+
+mod merge;
+pub use merge::{MergePolicy, MergeStrategy, Provenance};
+
+mod netif;
+pub use netif::{LinkDns, LinkDnsTable, NETWORKD_LINKS_DIR, RESOLVED_NETIF_DIR};
+
+mod resolved;
+pub use resolved::{
+    ResolvedConfig, ResolvedEvidence, ResolvedMode, ResolvedPaths, PROXY_STUB_ADDRESS,
+    STATIC_RESOLV_CONF, STUB_ADDRESS, STUB_RESOLV_CONF, UPLINK_RESOLV_CONF,
+};
+
+mod resolved_conf;
+pub use resolved_conf::{
+    DnsOverTls, Dnssec, ResolveSupport, ResolvedConf, ResolvedConfError, ResolvedDomain,
+    ResolvedServer, StubListener, RESOLVED_CONF, RESOLVED_CONF_DROPIN_DIRS,
+};
+
+mod options;
+pub use options::{OptionParseError, ResolverOption};
+
+mod parser;
+pub use parser::Parser;
+
+mod reader;
+pub use reader::{Limit, ParseLimits};
+
+mod reload;
+pub use reload::SystemConfig;
+
+mod resolvconf;
+pub use resolvconf::{
+    Resolvconf, ResolvconfError, ResolvconfOutput, DEFAULT_INTERFACE_ORDER, OPENRESOLV_CONF,
+    OPENRESOLV_INTERFACE_DIR, RESOLVCONF_DIR, RESOLVCONF_INTERFACE_DIR,
+};
+
+mod scutil;
+pub use scutil::{ScutilDns, ScutilParseError, ScutilResolver};
+
+mod source_map;
+pub use source_map::{SourceMap, Span};
+
+mod writer;
+pub use writer::{MaskStyle, Writer};
+
+#[cfg(feature = "serde")]
+mod serialize;
+
+mod split;
+pub use split::SplitDnsTable;
+
+#[cfg(target_os = "linux")]
+mod watch;
+#[cfg(all(target_os = "linux", feature = "stream"))]
+pub use watch::ConfigStream;
+#[cfg(target_os = "linux")]
+pub use watch::ConfigWatcher;
+
 /// Represent a resolver configuration, as described in `man 5 resolv.conf`.
 /// The options and defaults match those in the linux `man` page.
 ///
@@ -134,7 +212,7 @@ pub use ip::{AddrParseError, Network, ScopedIp};
 /// [`glibc_normalize`]: #method.glibc_normalize
 /// [`get_last_search_or_domain`]: #method.get_last_search_or_domain
 /// [`get_system_domain`]: #method.get_system_domain
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Debug)]
 pub struct Config {
     /// List of nameservers
     pub nameservers: Vec<ScopedIp>,
@@ -191,6 +269,24 @@ pub struct Config {
     pub family: Vec<Family>,
     /// Suppress AAAA queries made by the stub resolver
     pub no_aaaa: bool,
+    /// The options in the order they were parsed or applied, which the
+    /// fields above do not tell. The fields win when they disagree, see
+    /// `applied_options`. It is not compared by `PartialEq`, and is
+    /// serialized as the list of `options()`.
+    options: Vec<ResolverOption>,
+    /// Options this crate does not know, with their value if any, in the
+    /// order they were found. They are written back by `Display`.
+    pub unknown_options: Vec<(String, Option<String>)>,
+    /// Directives this crate does not know, with their arguments, in the
+    /// order they were found. They are written back by `Display`.
+    pub unknown_directives: Vec<(String, Vec<String>)>,
+    /// Values stored by the handlers of a [`Parser`] for directives and
+    /// options this crate does not know. They are not written back by
+    /// `Display`.
+    pub extensions: BTreeMap<String, String>,
+    /// Where each value was found, if requested. It is not compared by
+    /// `PartialEq`, nor serialized.
+    source_map: Option<SourceMap>,
 }
 
 impl Config {
@@ -254,164 +350,20 @@ impl Config {
 
     /// Parse a buffer and return a best-effort parsed `Config` object along with any errors.
     pub fn parse_with_errors(bytes: &[u8]) -> (Self, Vec<ParseError>) {
-        use ParseError::*;
         let mut cfg = Self::new();
         let mut errors = Vec::new();
-        'lines: for (lineno, line) in bytes.split(|&x| x == b'\n').enumerate() {
-            for &c in line.iter() {
-                if c != b'\t' && c != b' ' {
-                    if c == b';' || c == b'#' {
-                        continue 'lines;
-                    } else {
-                        break;
-                    }
-                }
-            }
-
-            // All that dances above to allow invalid utf-8 inside the comments
-            let str = match from_utf8(line) {
-                Ok(str) => str,
-                Err(e) => {
-                    errors.push(InvalidUtf8(lineno, e));
-                    continue;
-                }
-            };
-
-            // ignore everything after ';' or '#'
-            let text = match str.split([';', '#']).next() {
-                Some(text) => text,
-                None => {
-                    errors.push(InvalidValue(lineno));
-                    continue;
-                }
-            };
-
-            let mut words = text.split_whitespace();
-            let keyword = match words.next() {
-                Some(x) => x,
-                None => continue,
-            };
-
-            match keyword {
-                "nameserver" => {
-                    let srv = match words.next() {
-                        Some(srv) => srv,
-                        None => {
-                            errors.push(InvalidValue(lineno));
-                            continue;
-                        }
-                    };
-
-                    match ScopedIp::from_str(srv) {
-                        Ok(addr) => cfg.nameservers.push(addr),
-                        Err(e) => errors.push(InvalidIp(lineno, e)),
-                    }
-
-                    if words.next().is_some() {
-                        errors.push(ExtraData(lineno));
-                    }
-                }
-                "domain" => {
-                    let domain = match words.next() {
-                        Some(domain) => domain,
-                        None => {
-                            errors.push(InvalidValue(lineno));
-                            continue;
-                        }
-                    };
-
-                    cfg.set_domain(domain.to_owned());
-                    if words.next().is_some() {
-                        errors.push(ExtraData(lineno));
-                    }
-                }
-                "search" => {
-                    cfg.set_search(words.map(|x| x.to_string()).collect());
-                }
-                "sortlist" => {
-                    cfg.sortlist.clear();
-                    for pair in words {
-                        match Network::from_str(pair) {
-                            Ok(network) => cfg.sortlist.push(network),
-                            Err(e) => errors.push(InvalidIp(lineno, e)),
-                        }
-                    }
-                }
-                "options" => {
-                    for pair in words {
-                        let mut iter = pair.splitn(2, ':');
-                        let key = match iter.next() {
-                            Some(key) => key,
-                            None => {
-                                errors.push(InvalidValue(lineno));
-                                continue 'lines;
-                            }
-                        };
-
-                        let value = iter.next();
-                        if iter.next().is_some() {
-                            errors.push(ExtraData(lineno));
-                            continue 'lines;
-                        }
-
-                        match (key, value) {
-                            // TODO(tailhook) ensure that values are None?
-                            ("debug", _) => cfg.debug = true,
-                            ("ndots", Some(x)) => match u32::from_str(x) {
-                                Ok(ndots) => cfg.ndots = ndots,
-                                Err(_) => errors.push(InvalidOptionValue(lineno)),
-                            },
-                            ("timeout", Some(x)) => match u32::from_str(x) {
-                                Ok(timeout) => cfg.timeout = timeout,
-                                Err(_) => errors.push(InvalidOptionValue(lineno)),
-                            },
-                            ("attempts", Some(x)) => match u32::from_str(x) {
-                                Ok(attempts) => cfg.attempts = attempts,
-                                Err(_) => errors.push(InvalidOptionValue(lineno)),
-                            },
-                            ("rotate", _) => cfg.rotate = true,
-                            ("no-check-names", _) => cfg.no_check_names = true,
-                            ("inet6", _) => cfg.inet6 = true,
-                            ("ip6-bytestring", _) => cfg.ip6_bytestring = true,
-                            ("ip6-dotint", _) => cfg.ip6_dotint = true,
-                            ("no-ip6-dotint", _) => cfg.ip6_dotint = false,
-                            ("edns0", _) => cfg.edns0 = true,
-                            ("single-request", _) => cfg.single_request = true,
-                            ("single-request-reopen", _) => cfg.single_request_reopen = true,
-                            ("no-reload", _) => cfg.no_reload = true,
-                            ("trust-ad", _) => cfg.trust_ad = true,
-                            ("no-tld-query", _) => cfg.no_tld_query = true,
-                            ("use-vc", _) => cfg.use_vc = true,
-                            ("no-aaaa", _) => cfg.no_aaaa = true,
-                            _ => errors.push(InvalidOption(lineno)),
-                        }
-                    }
-                }
-                "lookup" => {
-                    for word in words {
-                        match word {
-                            "file" => cfg.lookup.push(Lookup::File),
-                            "bind" => cfg.lookup.push(Lookup::Bind),
-                            extra => cfg.lookup.push(Lookup::Extra(extra.to_string())),
-                        }
-                    }
-                }
-                "family" => {
-                    for word in words {
-                        match word {
-                            "inet4" => cfg.family.push(Family::Inet4),
-                            "inet6" => cfg.family.push(Family::Inet6),
-                            _ => errors.push(InvalidValue(lineno)),
-                        }
-                    }
-                }
-                _ => errors.push(InvalidDirective(lineno)),
-            }
+        for (lineno, line) in bytes.split(|&x| x == b'\n').enumerate() {
+            cfg.parse_line(lineno, line, &mut errors);
         }
 
         (cfg, errors)
     }
 
+    /// Apply a single line of a resolv.conf file, pushing its errors.
+    pub(crate) fn parse_line(&mut self, lineno: usize, line: &[u8], errors: &mut Vec<ParseError>) {
+        grammar::visit_line(lineno, line, &mut ConfigVisitor::new(self, errors));
+    }
+
     /// Return the suffixes declared in the last "domain" or "search" directive.
     ///
     /// ```rust
@@ -484,6 +436,16 @@ impl Config {
             s.truncate(SEARCH_LIMIT);
             s
         });
+        if let Some(map) = &mut self.source_map {
+            if map.nameservers.len() > NAMESERVER_LIMIT {
+                let dropped = map.nameservers.split_off(NAMESERVER_LIMIT);
+                map.overridden.extend(dropped);
+            }
+            if map.search.len() > SEARCH_LIMIT {
+                let dropped = map.search.split_off(SEARCH_LIMIT);
+                map.overridden.extend(dropped);
+            }
+        }
     }
 
     /// Get nameserver or on the local machine
@@ -498,6 +460,165 @@ impl Config {
         }
     }
 
+    /// Return the options in effect, in the order they were parsed or
+    /// applied, including repeated and unknown ones.
+    ///
+    /// The order is only known for the options parsed or applied with
+    /// [`apply_option`](#method.apply_option). An option that a field
+    /// written directly since then contradicts is left out, and the fields
+    /// written directly to a value that is not the default come last.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::{Config, ResolverOption};
+    /// # fn main() {
+    /// let mut config = Config::parse("options ndots:2 no-ip6-dotint rotate\noptions ndots:3").unwrap();
+    /// assert_eq!(config.options(), [
+    ///     ResolverOption::Ndots(2),
+    ///     ResolverOption::NoIp6Dotint,
+    ///     ResolverOption::Rotate,
+    ///     ResolverOption::Ndots(3),
+    /// ]);
+    /// assert_eq!(config.ndots, 3);
+    ///
+    /// config.rotate = false;
+    /// config.edns0 = true;
+    /// assert_eq!(config.options(), [
+    ///     ResolverOption::Ndots(2),
+    ///     ResolverOption::NoIp6Dotint,
+    ///     ResolverOption::Ndots(3),
+    ///     ResolverOption::Edns0,
+    /// ]);
+    /// # }
+    /// ```
+    pub fn options(&self) -> Vec<ResolverOption> {
+        let mut options = self.applied_options().cloned().collect::<Vec<_>>();
+        for option in options::set_options(self) {
+            if !options.iter().any(|o| o.name() == option.name()) {
+                options.push(option);
+            }
+        }
+        for (name, value) in &self.unknown_options {
+            let option = ResolverOption::Unknown(name.clone(), value.clone());
+            if !options.contains(&option) {
+                options.push(option);
+            }
+        }
+        options
+    }
+
+    /// The parsed or applied options that the fields still agree with. An
+    /// option is kept along with the repeated ones before it if the last
+    /// one setting the same field agrees.
+    fn applied_options(&self) -> impl Iterator<Item = &ResolverOption> {
+        self.options.iter().filter(move |option| {
+            let field = option.field();
+            self.options
+                .iter()
+                .rev()
+                .find(|last| last.field() == field)
+                .map_or(false, |last| options::agrees(self, last))
+        })
+    }
+
+    /// Apply an option, as if it was found after the existing ones.
+    pub fn apply_option(&mut self, option: ResolverOption) {
+        options::set_option!(&mut *self, &option);
+        if let ResolverOption::Unknown(name, value) = &option {
+            self.unknown_options.push((name.clone(), value.clone()));
+        }
+        self.options.push(option);
+    }
+
+    /// Apply the options of a `RES_OPTIONS` environment variable, which
+    /// glibc applies after the ones of the file. Nothing is applied if one
+    /// of them is invalid.
+    pub fn apply_res_options(&mut self, res_options: &str) -> Result<(), OptionParseError> {
+        for option in ResolverOption::parse_list(res_options)? {
+            self.apply_option(option);
+        }
+        Ok(())
+    }
+
+    /// Return whether the option `name` was parsed or applied, even if it
+    /// set the default value, and its field was not written directly since.
+    /// Both spellings of `ip6-dotint` and `no-ip6-dotint` are told apart;
+    /// see [`explicit_ip6_dotint`](#method.explicit_ip6_dotint) to know
+    /// whether either was set.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// # fn main() {
+    /// let mut config = Config::parse("options rotate ndots:1").unwrap();
+    /// assert!(config.is_explicit("rotate"));
+    /// config.rotate = false;
+    /// assert!(!config.is_explicit("rotate"));
+    /// # }
+    /// ```
+    pub fn is_explicit(&self, name: &str) -> bool {
+        self.applied_options().any(|option| option.name() == name)
+    }
+
+    /// Return `ndots` if an option set it, even to the default value, and
+    /// the field was not written directly since.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// # fn main() {
+    /// let config = Config::parse("options ndots:1").unwrap();
+    /// assert_eq!(config.ndots, 1);
+    /// assert_eq!(config.explicit_ndots(), Some(1));
+    /// assert_eq!(Config::new().explicit_ndots(), None);
+    /// assert_eq!(config.to_string(), "options ndots:1\n");
+    /// # }
+    /// ```
+    pub fn explicit_ndots(&self) -> Option<u32> {
+        self.explicit(
+            |option| matches!(option, ResolverOption::Ndots(_)),
+            self.ndots,
+        )
+    }
+
+    /// Return `timeout` if an option set it, even to the default value, and
+    /// the field was not written directly since.
+    pub fn explicit_timeout(&self) -> Option<u32> {
+        self.explicit(
+            |option| matches!(option, ResolverOption::Timeout(_)),
+            self.timeout,
+        )
+    }
+
+    /// Return `attempts` if an option set it, even to the default value,
+    /// and the field was not written directly since.
+    pub fn explicit_attempts(&self) -> Option<u32> {
+        self.explicit(
+            |option| matches!(option, ResolverOption::Attempts(_)),
+            self.attempts,
+        )
+    }
+
+    /// Return `ip6_dotint` if `ip6-dotint` or `no-ip6-dotint` set it.
+    pub fn explicit_ip6_dotint(&self) -> Option<bool> {
+        self.explicit(
+            |option| {
+                matches!(
+                    option,
+                    ResolverOption::Ip6Dotint | ResolverOption::NoIp6Dotint
+                )
+            },
+            self.ip6_dotint,
+        )
+    }
+
+    fn explicit<T>(&self, sets: fn(&ResolverOption) -> bool, value: T) -> Option<T> {
+        match self.applied_options().any(sets) {
+            true => Some(value),
+            false => None,
+        }
+    }
+
     /// Get domain from config or fallback to the suffix of a hostname
     ///
     /// This is how glibc finds out a hostname.
@@ -579,149 +700,53 @@ impl Default for Config {
             lookup: Vec::new(),
             family: Vec::new(),
             no_aaaa: false,
+            options: Vec::new(),
+            unknown_options: Vec::new(),
+            unknown_directives: Vec::new(),
+            extensions: BTreeMap::new(),
+            source_map: None,
         }
     }
 }
 
-impl fmt::Display for Config {
-    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
-        let Self {
-            nameservers,
-            last_search,
-            domain,
-            search,
-            sortlist,
-            debug,
-            ndots,
-            timeout,
-            attempts,
-            rotate,
-            no_check_names,
-            inet6,
-            ip6_bytestring,
-            ip6_dotint,
-            edns0,
-            single_request,
-            single_request_reopen,
-            no_tld_query,
-            use_vc,
-            no_reload,
-            trust_ad,
-            lookup,
-            family,
-            no_aaaa,
-        } = self;
-
-        for nameserver in nameservers.iter() {
-            writeln!(fmt, "nameserver {nameserver}")?;
-        }
-
-        if last_search != &LastSearch::Domain {
-            if let Some(domain) = domain {
-                writeln!(fmt, "domain {domain}")?;
-            }
-        }
-
-        if let Some(search) = search {
-            if !search.is_empty() {
-                write!(fmt, "search")?;
-                for suffix in search.iter() {
-                    write!(fmt, " {suffix}")?;
-                }
-                writeln!(fmt)?;
-            }
-        }
-
-        if last_search == &LastSearch::Domain {
-            if let Some(domain) = &self.domain {
-                writeln!(fmt, "domain {domain}")?;
-            }
-        }
-
-        if !sortlist.is_empty() {
-            write!(fmt, "sortlist")?;
-            for network in sortlist.iter() {
-                write!(fmt, " {network}")?;
-            }
-            writeln!(fmt)?;
-        }
-
-        if !lookup.is_empty() {
-            write!(fmt, "lookup")?;
-            for db in lookup.iter() {
-                match db {
-                    Lookup::File => write!(fmt, " file")?,
-                    Lookup::Bind => write!(fmt, " bind")?,
-                    Lookup::Extra(extra) => write!(fmt, " {extra}")?,
-                }
-            }
-            writeln!(fmt)?;
-        }
-
-        if !family.is_empty() {
-            write!(fmt, "family")?;
-            for fam in family.iter() {
-                match fam {
-                    Family::Inet4 => write!(fmt, " inet4")?,
-                    Family::Inet6 => write!(fmt, " inet6")?,
-                }
-            }
-            writeln!(fmt)?;
-        }
+impl PartialEq for Config {
+    fn eq(&self, other: &Self) -> bool {
+        // the order of the options is not part of the configuration
+        self.nameservers == other.nameservers
+            && self.last_search == other.last_search
+            && self.domain == other.domain
+            && self.search == other.search
+            && self.sortlist == other.sortlist
+            && self.debug == other.debug
+            && self.ndots == other.ndots
+            && self.timeout == other.timeout
+            && self.attempts == other.attempts
+            && self.rotate == other.rotate
+            && self.no_check_names == other.no_check_names
+            && self.inet6 == other.inet6
+            && self.ip6_bytestring == other.ip6_bytestring
+            && self.ip6_dotint == other.ip6_dotint
+            && self.edns0 == other.edns0
+            && self.single_request == other.single_request
+            && self.single_request_reopen == other.single_request_reopen
+            && self.no_tld_query == other.no_tld_query
+            && self.use_vc == other.use_vc
+            && self.no_reload == other.no_reload
+            && self.trust_ad == other.trust_ad
+            && self.lookup == other.lookup
+            && self.family == other.family
+            && self.no_aaaa == other.no_aaaa
+            && self.unknown_options == other.unknown_options
+            && self.unknown_directives == other.unknown_directives
+            && self.extensions == other.extensions
+    }
+}
 
-        if *debug {
-            writeln!(fmt, "options debug")?;
-        }
-        if *ndots != 1 {
-            writeln!(fmt, "options ndots:{}", self.ndots)?;
-        }
-        if *timeout != 5 {
-            writeln!(fmt, "options timeout:{}", self.timeout)?;
-        }
-        if *attempts != 2 {
-            writeln!(fmt, "options attempts:{}", self.attempts)?;
-        }
-        if *rotate {
-            writeln!(fmt, "options rotate")?;
-        }
-        if *no_check_names {
-            writeln!(fmt, "options no-check-names")?;
-        }
-        if *inet6 {
-            writeln!(fmt, "options inet6")?;
-        }
-        if *ip6_bytestring {
-            writeln!(fmt, "options ip6-bytestring")?;
-        }
-        if *ip6_dotint {
-            writeln!(fmt, "options ip6-dotint")?;
-        }
-        if *edns0 {
-            writeln!(fmt, "options edns0")?;
-        }
-        if *single_request {
-            writeln!(fmt, "options single-request")?;
-        }
-        if *single_request_reopen {
-            writeln!(fmt, "options single-request-reopen")?;
-        }
-        if *no_tld_query {
-            writeln!(fmt, "options no-tld-query")?;
-        }
-        if *use_vc {
-            writeln!(fmt, "options use-vc")?;
-        }
-        if *no_reload {
-            writeln!(fmt, "options no-reload")?;
-        }
-        if *trust_ad {
-            writeln!(fmt, "options trust-ad")?;
-        }
-        if *no_aaaa {
-            writeln!(fmt, "options no-aaaa")?;
-        }
+impl Eq for Config {}
 
-        Ok(())
+impl fmt::Display for Config {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        Writer::new().fmt_config(self, fmt)
     }
 }
 
@@ -744,6 +769,8 @@ pub enum ParseError {
     InvalidIp(usize, AddrParseError),
     /// Error returned when there is extra data at the end of a line.
     ExtraData(usize),
+    /// Error returned by [`Config::parse_reader`] when a [`ParseLimits`] limit is exceeded.
+    LimitExceeded(usize, Limit),
 }
 
 impl std::fmt::Display for ParseError {
@@ -768,6 +795,7 @@ impl std::fmt::Display for ParseError {
                 write!(f, "directive at line {line} contains invalid IP: {err}")
             }
             Self::ExtraData(line) => write!(f, "extra data at the end of line {line}"),
+            Self::LimitExceeded(line, limit) => write!(f, "{limit} exceeded at line {line}"),
         }
     }
 }
@@ -834,6 +862,16 @@ pub enum Family {
     Inet6,
 }
 
+impl Lookup {
+    fn from_word(word: &str) -> Self {
+        match word {
+            "file" => Self::File,
+            "bind" => Self::Bind,
+            extra => Self::Extra(extra.to_string()),
+        }
+    }
+}
+
 /// Parses the domain name from a hostname, if available
 fn domain_from_host(hostname: &[u8]) -> Option<&str> {
     let mut start = None;
@@ -0,0 +1,161 @@
+use std::error::Error;
+use std::fmt;
+use std::fs::File;
+use std::io::{self, Read};
+use std::path::{Path, PathBuf};
+
+use crate::{Config, ParseError};
+
+/// Path of the file read by [`Config::from_system`]
+pub const SYSTEM_RESOLV_CONF: &str = "/etc/resolv.conf";
+
+/// The fallback applied by [`Config::from_system`], like glibc does.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Fallback {
+    /// The file does not exist: the default options are used, with the
+    /// local nameservers
+    MissingFile,
+    /// The file lists no nameserver: the local nameservers are used
+    NoNameservers,
+}
+
+/// The configuration of the system, as returned by [`Config::from_system`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct SystemLoad {
+    /// The configuration, with the fallback applied
+    pub config: Config,
+    /// The file the configuration was read from, or would have been read
+    /// from if it existed
+    pub path: PathBuf,
+    /// The fallback applied, if any
+    pub fallback: Option<Fallback>,
+}
+
+impl Config {
+    /// Read and parse a configuration from `reader`.
+    pub fn from_reader<R: Read>(mut reader: R) -> Result<Self, LoadError> {
+        let mut buf = Vec::new();
+        reader
+            .read_to_end(&mut buf)
+            .map_err(|e| LoadError::Io(None, e))?;
+        Self::parse(buf).map_err(|e| LoadError::Parse(None, e))
+    }
+
+    /// Read and parse the file at `path`.
+    ///
+    /// Unlike [`from_system`](#method.from_system), a missing file is an
+    /// error and no fallback is applied.
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        load_path(path.as_ref()).map(|(config, _)| config)
+    }
+
+    /// Load `/etc/resolv.conf` the way glibc does.
+    ///
+    /// A missing file is not an error: the default configuration is used.
+    /// Like [`get_nameservers_or_local`](#method.get_nameservers_or_local),
+    /// the local nameservers are used when none is configured. The returned
+    /// [`SystemLoad`] tells which fallback, if any, was applied.
+    ///
+    /// ```rust,no_run
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// # fn main() {
+    /// let system = Config::from_system().unwrap();
+    /// if let Some(fallback) = system.fallback {
+    ///     println!("{}: using {:?}", system.path.display(), fallback);
+    /// }
+    /// println!("{:?}", system.config.nameservers);
+    /// # }
+    /// ```
+    pub fn from_system() -> Result<SystemLoad, LoadError> {
+        Self::from_system_path(SYSTEM_RESOLV_CONF)
+    }
+
+    /// Load the file at `path` with the fallback of
+    /// [`from_system`](#method.from_system).
+    pub fn from_system_path<P: AsRef<Path>>(path: P) -> Result<SystemLoad, LoadError> {
+        let path = path.as_ref();
+        let (mut config, mut fallback) = match Self::from_path(path) {
+            Ok(config) => (config, None),
+            Err(LoadError::NotFound(_)) => (Self::new(), Some(Fallback::MissingFile)),
+            Err(e) => return Err(e),
+        };
+        if config.nameservers.is_empty() {
+            config.nameservers = config.get_nameservers_or_local();
+            fallback = fallback.or(Some(Fallback::NoNameservers));
+        }
+        Ok(SystemLoad {
+            config,
+            path: path.to_owned(),
+            fallback,
+        })
+    }
+}
+
+/// Read and parse the file at `path` like [`Config::from_path`], and also
+/// return its content.
+pub(crate) fn load_path(path: &Path) -> Result<(Config, Vec<u8>), LoadError> {
+    let mut buf = Vec::new();
+    File::open(path)
+        .and_then(|mut file| file.read_to_end(&mut buf))
+        .map_err(|e| LoadError::from_io(path, e))?;
+    let config = Config::parse(&buf).map_err(|e| LoadError::Parse(Some(path.to_owned()), e))?;
+    Ok((config, buf))
+}
+
+/// Error while loading a resolv.conf file
+#[derive(Debug)]
+pub enum LoadError {
+    /// The file does not exist
+    NotFound(PathBuf),
+    /// The file can not be opened or read with the current permissions
+    PermissionDenied(PathBuf),
+    /// Another error occurred while reading, from the given path unless read
+    /// from a reader
+    Io(Option<PathBuf>, io::Error),
+    /// The content could not be parsed
+    Parse(Option<PathBuf>, ParseError),
+}
+
+impl LoadError {
+    pub(crate) fn from_io(path: &Path, err: io::Error) -> Self {
+        match err.kind() {
+            io::ErrorKind::NotFound => Self::NotFound(path.to_owned()),
+            io::ErrorKind::PermissionDenied => Self::PermissionDenied(path.to_owned()),
+            _ => Self::Io(Some(path.to_owned()), err),
+        }
+    }
+
+    /// Return the path of the file that failed to load, if it is known.
+    pub fn path(&self) -> Option<&Path> {
+        match self {
+            Self::NotFound(path) | Self::PermissionDenied(path) => Some(path),
+            Self::Io(path, _) | Self::Parse(path, _) => path.as_deref(),
+        }
+    }
+}
+
+impl fmt::Display for LoadError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::NotFound(path) => write!(f, "{} does not exist", path.display()),
+            Self::PermissionDenied(path) => {
+                write!(f, "permission denied reading {}", path.display())
+            }
+            Self::Io(Some(path), err) => write!(f, "failed to read {}: {err}", path.display()),
+            Self::Io(None, err) => write!(f, "failed to read configuration: {err}"),
+            Self::Parse(Some(path), err) => write!(f, "failed to parse {}: {err}", path.display()),
+            Self::Parse(None, err) => write!(f, "failed to parse configuration: {err}"),
+        }
+    }
+}
+
+impl Error for LoadError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::NotFound(_) | Self::PermissionDenied(_) => None,
+            Self::Io(_, err) => Some(err),
+            Self::Parse(_, err) => Some(err),
+        }
+    }
+}
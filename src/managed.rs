@@ -0,0 +1,134 @@
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::resolved::comment_lines;
+
+/// The program that owns a resolv.conf file and will overwrite it.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ManagedBy {
+    /// NetworkManager
+    NetworkManager,
+    /// systemd-resolved
+    SystemdResolved,
+    /// A DHCP client, such as dhclient or dhcpcd
+    Dhcp,
+    /// Debian's `resolvconf` or openresolv
+    Resolvconf,
+    /// SUSE's netconfig
+    Netconfig,
+    /// macOS `configd`
+    MacOs,
+    /// The Windows Subsystem for Linux
+    Wsl,
+    /// Nothing indicates the file is managed
+    Unmanaged,
+}
+
+/// What gave away the owner of a file.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OwnershipEvidence {
+    /// The file is a symlink to the given target
+    Symlink(PathBuf),
+    /// The file starts with the given comment
+    Header(String),
+}
+
+/// The owner of a resolv.conf file, with the evidence it was identified by.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{ManagedBy, Ownership};
+/// # fn main() {
+/// let ownership = Ownership::detect(b"# Generated by NetworkManager\nnameserver 10.0.0.1\n");
+/// assert_eq!(ownership.managed_by, ManagedBy::NetworkManager);
+/// assert!(ownership.is_managed());
+///
+/// let ownership = Ownership::detect(b"nameserver 10.0.0.1\n");
+/// assert_eq!(ownership.managed_by, ManagedBy::Unmanaged);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Ownership {
+    /// The owner, taken from the first piece of evidence
+    pub managed_by: ManagedBy,
+    /// Every piece of evidence found, the symlink target first
+    pub evidence: Vec<(ManagedBy, OwnershipEvidence)>,
+}
+
+/// Substrings of header comments, matched case-insensitively
+const HEADERS: &[(&str, ManagedBy)] = &[
+    ("generated by networkmanager", ManagedBy::NetworkManager),
+    ("man:systemd-resolved", ManagedBy::SystemdResolved),
+    ("/run/systemd/resolve/", ManagedBy::SystemdResolved),
+    ("generated by resolvconf", ManagedBy::Resolvconf),
+    ("do not edit this file by hand", ManagedBy::Resolvconf),
+    ("generated by netconfig", ManagedBy::Netconfig),
+    ("please use the netconfig", ManagedBy::Netconfig),
+    ("dhclient", ManagedBy::Dhcp),
+    ("generated by dhcpcd", ManagedBy::Dhcp),
+    ("mac os x notice", ManagedBy::MacOs),
+    ("macos notice", ManagedBy::MacOs),
+    ("generated by wsl", ManagedBy::Wsl),
+];
+
+/// Substrings of symlink targets
+const TARGETS: &[(&str, ManagedBy)] = &[
+    ("systemd/resolve/", ManagedBy::SystemdResolved),
+    ("systemd/resolv.conf", ManagedBy::SystemdResolved),
+    ("NetworkManager/", ManagedBy::NetworkManager),
+    ("resolvconf/", ManagedBy::Resolvconf),
+    ("netconfig/", ManagedBy::Netconfig),
+    ("/mnt/wsl/", ManagedBy::Wsl),
+];
+
+impl Ownership {
+    /// Detect the owner of a file from its header comments.
+    pub fn detect(buf: &[u8]) -> Self {
+        let mut ownership = Self {
+            managed_by: ManagedBy::Unmanaged,
+            evidence: Vec::new(),
+        };
+        ownership.add_headers(buf);
+        ownership
+    }
+
+    /// Detect the owner of a file from its symlink target, if it is a
+    /// symlink, and from its header comments.
+    pub fn detect_path<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let path = path.as_ref();
+        let mut ownership = Self {
+            managed_by: ManagedBy::Unmanaged,
+            evidence: Vec::new(),
+        };
+        if let Ok(target) = fs::read_link(path) {
+            let text = target.to_string_lossy();
+            if let Some((_, owner)) = TARGETS.iter().find(|(pattern, _)| text.contains(pattern)) {
+                ownership.push(*owner, OwnershipEvidence::Symlink(target.clone()));
+            }
+        }
+        ownership.add_headers(&fs::read(path)?);
+        Ok(ownership)
+    }
+
+    /// Whether some program owns the file, so editing it by hand is futile.
+    pub fn is_managed(&self) -> bool {
+        self.managed_by != ManagedBy::Unmanaged
+    }
+
+    fn add_headers(&mut self, buf: &[u8]) {
+        for comment in comment_lines(buf) {
+            let lower = comment.to_lowercase();
+            if let Some((_, owner)) = HEADERS.iter().find(|(pattern, _)| lower.contains(pattern)) {
+                self.push(*owner, OwnershipEvidence::Header(comment.to_owned()));
+            }
+        }
+    }
+
+    fn push(&mut self, owner: ManagedBy, evidence: OwnershipEvidence) {
+        if self.evidence.is_empty() {
+            self.managed_by = owner;
+        }
+        self.evidence.push((owner, evidence));
+    }
+}
@@ -0,0 +1,287 @@
+use std::collections::BTreeMap;
+
+use crate::options::set_options;
+use crate::{Config, LastSearch, Network, ResolverOption, ScopedIp};
+
+/// How a list of an overlay is combined with the same list of the layers
+/// below it. An empty list in the overlay always leaves the list below
+/// untouched.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MergeStrategy {
+    /// The list of the overlay replaces the one below
+    Replace,
+    /// The entries of the overlay are added after the ones below, skipping
+    /// those already present
+    Append,
+    /// The entries of the overlay are added before the ones below, skipping
+    /// those already present
+    Prepend,
+}
+
+/// How [`Config::merge`] combines the lists of two configurations.
+///
+/// Options, `domain`, `lookup` and `family` are not lists to combine: the
+/// last layer setting them wins.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct MergePolicy {
+    /// Strategy for the `nameserver` directives
+    pub nameservers: MergeStrategy,
+    /// Strategy for the search list, made of the last `domain` or `search`
+    /// directive of each layer
+    pub search: MergeStrategy,
+    /// Strategy for the `sortlist` directive
+    pub sortlist: MergeStrategy,
+}
+
+impl Default for MergePolicy {
+    fn default() -> Self {
+        Self {
+            nameservers: MergeStrategy::Replace,
+            search: MergeStrategy::Replace,
+            sortlist: MergeStrategy::Replace,
+        }
+    }
+}
+
+/// Which layer each part of a configuration built by
+/// [`Config::merge_layers`] comes from.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Provenance {
+    /// Each nameserver, with the name of the layer that contributed it
+    pub nameservers: Vec<(ScopedIp, String)>,
+    /// Each domain of the search list, with the name of its layer
+    pub search: Vec<(String, String)>,
+    /// Each network of the sortlist, with the name of its layer
+    pub sortlist: Vec<(Network, String)>,
+    /// The name of the layer of the `domain`, if any
+    pub domain: Option<String>,
+    /// The name of the last layer that set each option, by option name,
+    /// unknown options included
+    pub options: BTreeMap<String, String>,
+    /// The name of the layer of the `lookup` databases, if any
+    pub lookup: Option<String>,
+    /// The name of the layer of the `family` list, if any
+    pub family: Option<String>,
+    /// The name of the last layer that had each unknown directive, by
+    /// keyword
+    pub unknown_directives: BTreeMap<String, String>,
+    /// The name of the last layer that set each extension value, by key
+    pub extensions: BTreeMap<String, String>,
+}
+
+impl Config {
+    /// Merge `overlay` on top of this configuration.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::{Config, MergePolicy, MergeStrategy};
+    /// # fn main() {
+    /// let base = Config::parse("nameserver 10.0.0.1\nsearch example.com\noptions ndots:2").unwrap();
+    /// let vpn = Config::parse("nameserver 10.8.0.1\nsearch corp.example.com\noptions rotate").unwrap();
+    ///
+    /// let policy = MergePolicy {
+    ///     nameservers: MergeStrategy::Prepend,
+    ///     search: MergeStrategy::Append,
+    ///     ..MergePolicy::default()
+    /// };
+    /// let merged = base.merge(&vpn, policy);
+    /// assert_eq!(merged.to_string(), "\
+    /// nameserver 10.8.0.1
+    /// nameserver 10.0.0.1
+    /// search example.com corp.example.com
+    /// options ndots:2
+    /// options rotate
+    /// ");
+    /// # }
+    /// ```
+    pub fn merge(&self, overlay: &Self, policy: MergePolicy) -> Self {
+        Self::merge_layers([("base", self), ("overlay", overlay)], policy).0
+    }
+
+    /// Merge named layers, each on top of the previous ones, and tell which
+    /// layer every part of the result comes from. The result is empty if
+    /// there are no layers.
+    pub fn merge_layers<'a, I, S>(layers: I, policy: MergePolicy) -> (Self, Provenance)
+    where
+        I: IntoIterator<Item = (S, &'a Self)>,
+        S: AsRef<str>,
+    {
+        let mut layers = layers.into_iter();
+        let (mut config, mut provenance) = match layers.next() {
+            Some((name, first)) => (first.clone(), Provenance::of(name.as_ref(), first)),
+            None => return (Self::new(), Provenance::default()),
+        };
+        for (name, overlay) in layers {
+            // the values now come from several buffers
+            config.source_map = None;
+            merge_into(&mut config, &mut provenance, name.as_ref(), overlay, policy);
+        }
+        (config, provenance)
+    }
+}
+
+impl Provenance {
+    /// Attribute every part of `config` to `layer`
+    fn of(layer: &str, config: &Config) -> Self {
+        let mut options = BTreeMap::new();
+        for option in set_options(config) {
+            options.insert(option.name().to_owned(), layer.to_owned());
+        }
+        for (name, _) in &config.unknown_options {
+            options.insert(name.clone(), layer.to_owned());
+        }
+        Self {
+            nameservers: tag_cloned(&config.nameservers, layer),
+            search: config
+                .get_last_search_or_domain()
+                .map(|domain| (domain.clone(), layer.to_owned()))
+                .collect(),
+            sortlist: tag_cloned(&config.sortlist, layer),
+            domain: config.domain.as_ref().map(|_| layer.to_owned()),
+            options,
+            lookup: tag_non_empty(&config.lookup, layer),
+            family: tag_non_empty(&config.family, layer),
+            unknown_directives: config
+                .unknown_directives
+                .iter()
+                .map(|(keyword, _)| (keyword.clone(), layer.to_owned()))
+                .collect(),
+            extensions: config
+                .extensions
+                .keys()
+                .map(|key| (key.clone(), layer.to_owned()))
+                .collect(),
+        }
+    }
+}
+
+fn tag_non_empty<T>(items: &[T], layer: &str) -> Option<String> {
+    match items.is_empty() {
+        true => None,
+        false => Some(layer.to_owned()),
+    }
+}
+
+fn tag_cloned<T: Clone>(items: &[T], layer: &str) -> Vec<(T, String)> {
+    items
+        .iter()
+        .map(|item| (item.clone(), layer.to_owned()))
+        .collect()
+}
+
+fn merge_into(
+    config: &mut Config,
+    provenance: &mut Provenance,
+    layer: &str,
+    overlay: &Config,
+    policy: MergePolicy,
+) {
+    merge_list(
+        &mut provenance.nameservers,
+        &overlay.nameservers,
+        layer,
+        policy.nameservers,
+    );
+    config.nameservers = untag(&provenance.nameservers);
+
+    merge_list(
+        &mut provenance.sortlist,
+        &overlay.sortlist,
+        layer,
+        policy.sortlist,
+    );
+    config.sortlist = untag(&provenance.sortlist);
+
+    // the domain is kept even when a later `search` hides it, like in a
+    // single file
+    if let Some(domain) = &overlay.domain {
+        config.domain = Some(domain.clone());
+        provenance.domain = Some(layer.to_owned());
+    }
+    let search = overlay
+        .get_last_search_or_domain()
+        .cloned()
+        .collect::<Vec<_>>();
+    merge_list(&mut provenance.search, &search, layer, policy.search);
+    match (overlay.last_search, policy.search) {
+        (LastSearch::None, _) => {}
+        // the last directive of the overlay wins, as if it was appended
+        (LastSearch::Domain, MergeStrategy::Replace) => config.last_search = LastSearch::Domain,
+        _ => config.set_search(untag(&provenance.search)),
+    }
+
+    for option in set_options(overlay) {
+        provenance
+            .options
+            .insert(option.name().to_owned(), layer.to_owned());
+        config.apply_option(option);
+    }
+    for (name, value) in &overlay.unknown_options {
+        config.unknown_options.retain(|(n, _)| n != name);
+        config
+            .options
+            .retain(|o| !matches!(o, ResolverOption::Unknown(n, _) if n == name));
+        provenance.options.insert(name.clone(), layer.to_owned());
+        config.apply_option(ResolverOption::Unknown(name.clone(), value.clone()));
+    }
+
+    if !overlay.lookup.is_empty() {
+        config.lookup = overlay.lookup.clone();
+        provenance.lookup = Some(layer.to_owned());
+    }
+    if !overlay.family.is_empty() {
+        config.family = overlay.family.clone();
+        provenance.family = Some(layer.to_owned());
+    }
+    config
+        .unknown_directives
+        .retain(|(keyword, _)| !overlay.unknown_directives.iter().any(|(k, _)| k == keyword));
+    for (keyword, args) in &overlay.unknown_directives {
+        config
+            .unknown_directives
+            .push((keyword.clone(), args.clone()));
+        provenance
+            .unknown_directives
+            .insert(keyword.clone(), layer.to_owned());
+    }
+    for (key, value) in &overlay.extensions {
+        config.extensions.insert(key.clone(), value.clone());
+        provenance.extensions.insert(key.clone(), layer.to_owned());
+    }
+}
+
+/// Combine the tagged `list` with the entries of `layer`
+fn merge_list<T: Clone + PartialEq>(
+    list: &mut Vec<(T, String)>,
+    entries: &[T],
+    layer: &str,
+    strategy: MergeStrategy,
+) {
+    if entries.is_empty() {
+        return;
+    }
+    let tagged = tag_cloned(entries, layer);
+    match strategy {
+        MergeStrategy::Replace => *list = tagged,
+        MergeStrategy::Append => {
+            for (entry, layer) in tagged {
+                if !list.iter().any(|(e, _)| *e == entry) {
+                    list.push((entry, layer));
+                }
+            }
+        }
+        MergeStrategy::Prepend => {
+            let mut merged = Vec::with_capacity(list.len() + tagged.len());
+            for (entry, layer) in tagged.into_iter().chain(list.drain(..)) {
+                if !merged.iter().any(|(e, _): &(T, String)| *e == entry) {
+                    merged.push((entry, layer));
+                }
+            }
+            *list = merged;
+        }
+    }
+}
+
+fn untag<T: Clone>(list: &[(T, String)]) -> Vec<T> {
+    list.iter().map(|(entry, _)| entry.clone()).collect()
+}
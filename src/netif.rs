@@ -0,0 +1,216 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::resolved_conf::parse_boolean;
+use crate::split::suffix_labels;
+use crate::{ResolvedConfError, ResolvedDomain, ResolvedServer, ScopedIp};
+
+/// Directory where systemd-resolved saves the per-link DNS state
+pub const RESOLVED_NETIF_DIR: &str = "/run/systemd/resolve/netif";
+/// Directory where systemd-networkd saves the per-link state
+pub const NETWORKD_LINKS_DIR: &str = "/run/systemd/netif/links";
+
+/// The DNS settings of a single network link.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkDns {
+    /// Index of the link
+    pub ifindex: u32,
+    /// Nameservers of the link. IPv6 link-local addresses without an
+    /// explicit scope are scoped to the link.
+    pub servers: Vec<ScopedIp>,
+    /// Search and routing domains of the link
+    pub domains: Vec<ResolvedDomain>,
+    /// Whether the link was explicitly marked (or unmarked) as the default
+    /// route for queries
+    pub default_route: Option<bool>,
+    /// State files the settings were read from
+    pub sources: Vec<PathBuf>,
+}
+
+impl LinkDns {
+    /// Return the domains used to complete single-label names.
+    pub fn search_domains(&self) -> impl Iterator<Item = &str> {
+        self.domains
+            .iter()
+            .filter(|domain| !domain.route_only)
+            .map(|domain| domain.name.as_str())
+    }
+
+    /// Return the domains only used to route queries.
+    pub fn route_domains(&self) -> impl Iterator<Item = &str> {
+        self.domains
+            .iter()
+            .filter(|domain| domain.route_only)
+            .map(|domain| domain.name.as_str())
+    }
+
+    /// Whether queries that match no domain of any link may go to this link.
+    ///
+    /// As in systemd-resolved, a link without an explicit `DefaultRoute=`
+    /// setting is a default route unless it has routing-only domains; `~.`
+    /// always makes it one.
+    pub fn is_default_route(&self) -> bool {
+        if self
+            .route_domains()
+            .any(|domain| domain == "." || domain.is_empty())
+        {
+            return true;
+        }
+        self.default_route
+            .unwrap_or_else(|| self.route_domains().next().is_none())
+    }
+
+    /// Return the number of labels of the longest domain of this link that
+    /// `name` falls under, if any.
+    pub fn match_labels(&self, name: &str) -> Option<usize> {
+        self.domains
+            .iter()
+            .filter_map(|domain| suffix_labels(name, &domain.name))
+            .max()
+    }
+}
+
+/// The DNS settings of every link, keyed by interface index.
+///
+/// The table is built from the state files systemd-resolved and
+/// systemd-networkd keep under `/run`, so no D-Bus connection is needed.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct LinkDnsTable {
+    /// Links by interface index
+    pub links: BTreeMap<u32, LinkDns>,
+}
+
+impl LinkDnsTable {
+    /// Read the state files from their default locations.
+    pub fn load() -> Result<Self, ResolvedConfError> {
+        Self::load_from(Path::new(RESOLVED_NETIF_DIR), Path::new(NETWORKD_LINKS_DIR))
+    }
+
+    /// Read the state files from the given directories, either of which may
+    /// be missing.
+    ///
+    /// The runtime state of systemd-resolved takes precedence over the one
+    /// of systemd-networkd: a setting from the networkd file is only used if
+    /// the resolved file does not have it.
+    pub fn load_from(
+        resolved_netif: &Path,
+        networkd_links: &Path,
+    ) -> Result<Self, ResolvedConfError> {
+        let mut table = Self::default();
+        for dir in &[resolved_netif, networkd_links] {
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(ResolvedConfError::Io(dir.to_path_buf(), e)),
+            };
+            let mut paths = Vec::new();
+            for entry in entries {
+                let path = entry
+                    .map_err(|e| ResolvedConfError::Io(dir.to_path_buf(), e))?
+                    .path();
+                let ifindex = path
+                    .file_name()
+                    .and_then(|name| name.to_str())
+                    .and_then(|name| u32::from_str(name).ok());
+                if let Some(ifindex) = ifindex {
+                    paths.push((ifindex, path));
+                }
+            }
+            paths.sort();
+
+            for (ifindex, path) in paths {
+                let text = fs::read_to_string(&path)
+                    .map_err(|e| ResolvedConfError::Io(path.clone(), e))?;
+                let link = table.links.entry(ifindex).or_insert_with(|| LinkDns {
+                    ifindex,
+                    ..LinkDns::default()
+                });
+                apply(link, &text)
+                    .map_err(|e| ResolvedConfError::InFile(path.clone(), Box::new(e)))?;
+                link.sources.push(path);
+            }
+        }
+        Ok(table)
+    }
+
+    /// Return the link with the given interface index.
+    pub fn get(&self, ifindex: u32) -> Option<&LinkDns> {
+        self.links.get(&ifindex)
+    }
+
+    /// Return the links systemd-resolved would send a query for `name` to.
+    ///
+    /// These are the links with the longest domain matching `name`, or the
+    /// default route links when no domain matches. Links without servers are
+    /// never returned.
+    pub fn route(&self, name: &str) -> Vec<&LinkDns> {
+        let candidates = self.links.values().filter(|link| !link.servers.is_empty());
+        let best = candidates
+            .clone()
+            .filter_map(|link| link.match_labels(name))
+            .max();
+        match best {
+            Some(best) => candidates
+                .filter(|link| link.match_labels(name) == Some(best))
+                .collect(),
+            None => candidates.filter(|link| link.is_default_route()).collect(),
+        }
+    }
+}
+
+/// Apply a state file to a link, keeping the settings that are already set.
+fn apply(link: &mut LinkDns, text: &str) -> Result<(), ResolvedConfError> {
+    let had_servers = !link.servers.is_empty();
+    let had_domains = !link.domains.is_empty();
+    for (lineno, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (key, value) = match line.split_once('=') {
+            Some((key, value)) => (key, value.trim()),
+            None => return Err(ResolvedConfError::InvalidLine(lineno)),
+        };
+
+        match key {
+            "SERVERS" | "DNS" if !had_servers => {
+                for word in value.split_whitespace() {
+                    let server = ResolvedServer::from_str(word)
+                        .map_err(|e| ResolvedConfError::InvalidServer(lineno, e))?;
+                    link.servers.push(scoped_server(&server, link.ifindex));
+                }
+            }
+            "DOMAINS" if !had_domains => link
+                .domains
+                .extend(value.split_whitespace().map(ResolvedDomain::from_word)),
+            "ROUTE_DOMAINS" if !had_domains => {
+                link.domains
+                    .extend(value.split_whitespace().map(|word| ResolvedDomain {
+                        name: word.trim_start_matches('~').to_owned(),
+                        route_only: true,
+                    }))
+            }
+            "DEFAULT_ROUTE" | "DNS_DEFAULT_ROUTE" if link.default_route.is_none() => {
+                match parse_boolean(value) {
+                    Some(value) => link.default_route = Some(value),
+                    None => return Err(ResolvedConfError::InvalidValue(lineno)),
+                }
+            }
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+fn scoped_server(server: &ResolvedServer, ifindex: u32) -> ScopedIp {
+    match (server.ip, &server.interface) {
+        (IpAddr::V6(ip), None) if ip.segments()[0] & 0xffc0 == 0xfe80 => {
+            ScopedIp::V6(ip, Some(ifindex.to_string()))
+        }
+        _ => server.to_scoped_ip(),
+    }
+}
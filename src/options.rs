@@ -0,0 +1,320 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::grammar::split_option;
+use crate::{Config, ParseError};
+
+/// An option of the `options` directive.
+///
+/// The same spelling is used in resolv.conf, in the `RES_OPTIONS`
+/// environment variable, and in the `dnsConfig.options` of a Kubernetes pod,
+/// so this type parses all of them:
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::ResolverOption;
+/// # fn main() {
+/// let options = ResolverOption::parse_list("ndots:2 rotate use-vc").unwrap();
+/// assert_eq!(options, [ResolverOption::Ndots(2), ResolverOption::Rotate, ResolverOption::UseVc]);
+///
+/// // `{ name: ndots, value: "5" }` in a pod spec
+/// let option = ResolverOption::from_name_value("ndots", Some("5")).unwrap();
+/// assert_eq!(option, ResolverOption::Ndots(5));
+/// assert_eq!(option.to_string(), "ndots:5");
+///
+/// let option: ResolverOption = "strict-error".parse().unwrap();
+/// assert_eq!(option, ResolverOption::Unknown("strict-error".into(), None));
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolverOption {
+    /// `debug`
+    Debug,
+    /// `ndots:n`
+    Ndots(u32),
+    /// `timeout:n`
+    Timeout(u32),
+    /// `attempts:n`
+    Attempts(u32),
+    /// `rotate`
+    Rotate,
+    /// `no-check-names`
+    NoCheckNames,
+    /// `inet6`
+    Inet6,
+    /// `ip6-bytestring`
+    Ip6Bytestring,
+    /// `ip6-dotint`
+    Ip6Dotint,
+    /// `no-ip6-dotint`
+    NoIp6Dotint,
+    /// `edns0`
+    Edns0,
+    /// `single-request`
+    SingleRequest,
+    /// `single-request-reopen`
+    SingleRequestReopen,
+    /// `no-reload`
+    NoReload,
+    /// `trust-ad`
+    TrustAd,
+    /// `no-tld-query`
+    NoTldQuery,
+    /// `use-vc`
+    UseVc,
+    /// `no-aaaa`
+    NoAaaa,
+    /// An option this crate does not know, with its value if any. Like
+    /// glibc, an option that needs a value but has none is unknown.
+    Unknown(String, Option<String>),
+}
+
+impl ResolverOption {
+    /// Build an option from its name and value, as found in the
+    /// `dnsConfig.options` of a Kubernetes pod.
+    pub fn from_name_value(name: &str, value: Option<&str>) -> Result<Self, OptionParseError> {
+        if name.is_empty() {
+            return Err(OptionParseError::Empty);
+        }
+        match Self::decode(name, value, 0) {
+            Ok(Some(option)) => Ok(option),
+            Ok(None) => Ok(Self::Unknown(name.to_owned(), value.map(str::to_owned))),
+            Err(_) => Err(OptionParseError::InvalidValue(name.to_owned())),
+        }
+    }
+
+    /// Parse a whitespace-separated list of options, as found after the
+    /// `options` keyword or in the `RES_OPTIONS` environment variable.
+    pub fn parse_list(options: &str) -> Result<Vec<Self>, OptionParseError> {
+        options.split_whitespace().map(Self::from_str).collect()
+    }
+
+    /// Return the name of the option, as written before the colon.
+    pub fn name(&self) -> &str {
+        use ResolverOption::*;
+        match self {
+            Debug => "debug",
+            Ndots(_) => "ndots",
+            Timeout(_) => "timeout",
+            Attempts(_) => "attempts",
+            Rotate => "rotate",
+            NoCheckNames => "no-check-names",
+            Inet6 => "inet6",
+            Ip6Bytestring => "ip6-bytestring",
+            Ip6Dotint => "ip6-dotint",
+            NoIp6Dotint => "no-ip6-dotint",
+            Edns0 => "edns0",
+            SingleRequest => "single-request",
+            SingleRequestReopen => "single-request-reopen",
+            NoReload => "no-reload",
+            TrustAd => "trust-ad",
+            NoTldQuery => "no-tld-query",
+            UseVc => "use-vc",
+            NoAaaa => "no-aaaa",
+            Unknown(name, _) => name,
+        }
+    }
+
+    /// Return the name of the field the option sets, which is the same for
+    /// `ip6-dotint` and `no-ip6-dotint`.
+    pub(crate) fn field(&self) -> &str {
+        match self {
+            Self::NoIp6Dotint => "ip6-dotint",
+            option => option.name(),
+        }
+    }
+
+    /// Decode an option from its name and value. Return `None` for an
+    /// unknown name, and the error to report for an invalid value.
+    pub(crate) fn decode(
+        key: &str,
+        value: Option<&str>,
+        lineno: usize,
+    ) -> Result<Option<Self>, ParseError> {
+        use ResolverOption::*;
+        let number =
+            |value: &str| u32::from_str(value).map_err(|_| ParseError::InvalidOptionValue(lineno));
+        Ok(Some(match (key, value) {
+            // TODO(tailhook) ensure that values are None?
+            ("debug", _) => Debug,
+            ("ndots", Some(x)) => Ndots(number(x)?),
+            ("timeout", Some(x)) => Timeout(number(x)?),
+            ("attempts", Some(x)) => Attempts(number(x)?),
+            ("rotate", _) => Rotate,
+            ("no-check-names", _) => NoCheckNames,
+            ("inet6", _) => Inet6,
+            ("ip6-bytestring", _) => Ip6Bytestring,
+            ("ip6-dotint", _) => Ip6Dotint,
+            ("no-ip6-dotint", _) => NoIp6Dotint,
+            ("edns0", _) => Edns0,
+            ("single-request", _) => SingleRequest,
+            ("single-request-reopen", _) => SingleRequestReopen,
+            ("no-reload", _) => NoReload,
+            ("trust-ad", _) => TrustAd,
+            ("no-tld-query", _) => NoTldQuery,
+            ("use-vc", _) => UseVc,
+            ("no-aaaa", _) => NoAaaa,
+            _ => return Ok(None),
+        }))
+    }
+}
+
+impl FromStr for ResolverOption {
+    type Err = OptionParseError;
+
+    /// Parse an option as written in resolv.conf, such as `ndots:2`.
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let (name, value) = split_option(s);
+        Self::from_name_value(name, value)
+    }
+}
+
+impl fmt::Display for ResolverOption {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        use ResolverOption::*;
+        match self {
+            Ndots(value) | Timeout(value) | Attempts(value) => {
+                write!(f, "{}:{value}", self.name())
+            }
+            Unknown(name, Some(value)) => write!(f, "{name}:{value}"),
+            _ => f.write_str(self.name()),
+        }
+    }
+}
+
+/// Error while parsing a [`ResolverOption`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum OptionParseError {
+    /// The option has no name
+    Empty,
+    /// The value of the given option is not a valid number
+    InvalidValue(String),
+}
+
+impl fmt::Display for OptionParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "empty option"),
+            Self::InvalidValue(name) => write!(f, "invalid value for option {name}"),
+        }
+    }
+}
+
+impl Error for OptionParseError {}
+
+/// Whether the fields of `config` have the value `option` sets
+pub(crate) fn agrees(config: &Config, option: &ResolverOption) -> bool {
+    use ResolverOption::*;
+    match option {
+        Debug => config.debug,
+        Ndots(value) => config.ndots == *value,
+        Timeout(value) => config.timeout == *value,
+        Attempts(value) => config.attempts == *value,
+        Rotate => config.rotate,
+        NoCheckNames => config.no_check_names,
+        Inet6 => config.inet6,
+        Ip6Bytestring => config.ip6_bytestring,
+        Ip6Dotint => config.ip6_dotint,
+        NoIp6Dotint => !config.ip6_dotint,
+        Edns0 => config.edns0,
+        SingleRequest => config.single_request,
+        SingleRequestReopen => config.single_request_reopen,
+        NoReload => config.no_reload,
+        TrustAd => config.trust_ad,
+        NoTldQuery => config.no_tld_query,
+        UseVc => config.use_vc,
+        NoAaaa => config.no_aaaa,
+        Unknown(name, value) => config
+            .unknown_options
+            .iter()
+            .any(|(n, v)| n == name && v == value),
+    }
+}
+
+/// The known options of `config` that were set explicitly or differ from
+/// the defaults, with their current value
+pub(crate) fn set_options(config: &Config) -> Vec<ResolverOption> {
+    known_options(config, false)
+}
+
+/// The known options of `config` in the order they are written: the ones
+/// of [`set_options`], and `ndots`, `timeout` and `attempts` in any case if
+/// `explicit_defaults`
+pub(crate) fn known_options(config: &Config, explicit_defaults: bool) -> Vec<ResolverOption> {
+    use ResolverOption::*;
+    let defaults = Config::new();
+    let mut options = Vec::new();
+    if config.debug {
+        options.push(Debug);
+    }
+    if config.ndots != defaults.ndots || config.explicit_ndots().is_some() || explicit_defaults {
+        options.push(Ndots(config.ndots));
+    }
+    if config.timeout != defaults.timeout
+        || config.explicit_timeout().is_some()
+        || explicit_defaults
+    {
+        options.push(Timeout(config.timeout));
+    }
+    if config.attempts != defaults.attempts
+        || config.explicit_attempts().is_some()
+        || explicit_defaults
+    {
+        options.push(Attempts(config.attempts));
+    }
+    let no_ip6_dotint = !config.ip6_dotint && config.explicit_ip6_dotint().is_some();
+    let flags = [
+        (config.rotate, Rotate),
+        (config.no_check_names, NoCheckNames),
+        (config.inet6, Inet6),
+        (config.ip6_bytestring, Ip6Bytestring),
+        (config.ip6_dotint, Ip6Dotint),
+        (no_ip6_dotint, NoIp6Dotint),
+        (config.edns0, Edns0),
+        (config.single_request, SingleRequest),
+        (config.single_request_reopen, SingleRequestReopen),
+        (config.no_tld_query, NoTldQuery),
+        (config.use_vc, UseVc),
+        (config.no_reload, NoReload),
+        (config.trust_ad, TrustAd),
+        (config.no_aaaa, NoAaaa),
+    ];
+    for (set, option) in flags {
+        if set {
+            options.push(option);
+        }
+    }
+    options
+}
+
+/// Set the field of `$config` corresponding to a [`ResolverOption`]. Both
+/// `Config` and `ConfigRef` have these fields.
+macro_rules! set_option {
+    ($config:expr, $option:expr) => {{
+        use $crate::ResolverOption::*;
+        let config = $config;
+        match $option {
+            Debug => config.debug = true,
+            Ndots(ndots) => config.ndots = *ndots,
+            Timeout(timeout) => config.timeout = *timeout,
+            Attempts(attempts) => config.attempts = *attempts,
+            Rotate => config.rotate = true,
+            NoCheckNames => config.no_check_names = true,
+            Inet6 => config.inet6 = true,
+            Ip6Bytestring => config.ip6_bytestring = true,
+            Ip6Dotint => config.ip6_dotint = true,
+            NoIp6Dotint => config.ip6_dotint = false,
+            Edns0 => config.edns0 = true,
+            SingleRequest => config.single_request = true,
+            SingleRequestReopen => config.single_request_reopen = true,
+            NoReload => config.no_reload = true,
+            TrustAd => config.trust_ad = true,
+            NoTldQuery => config.no_tld_query = true,
+            UseVc => config.use_vc = true,
+            NoAaaa => config.no_aaaa = true,
+            Unknown(..) => {}
+        }
+    }};
+}
+pub(crate) use set_option;
@@ -0,0 +1,123 @@
+use std::collections::BTreeMap;
+use std::fmt;
+
+use crate::grammar::{self, ConfigVisitor};
+use crate::{Config, ParseError};
+
+type DirectiveHandler =
+    dyn Fn(usize, &str, &mut BTreeMap<String, String>) -> Result<(), ParseError> + Send + Sync;
+type OptionHandler = dyn Fn(usize, Option<&str>, &mut BTreeMap<String, String>) -> Result<(), ParseError>
+    + Send
+    + Sync;
+
+/// A parser that accepts extra directives and options.
+///
+/// Each handler receives the line number, the arguments of the directive or
+/// the value of the option, and the [`extensions`](struct.Config.html#structfield.extensions)
+/// of the configuration being built, to store what it found. An error
+/// returned by a handler is reported like any other parse error.
+///
+/// Built-in directives and options always take precedence over handlers.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{ParseError, Parser};
+/// # fn main() {
+/// let parser = Parser::new()
+///     .directive("port", |lineno, args, extensions| {
+///         let port = args.trim();
+///         port.parse::<u16>().map_err(|_| ParseError::InvalidValue(lineno))?;
+///         extensions.insert("port".into(), port.into());
+///         Ok(())
+///     })
+///     .option("retrans", |lineno, value, extensions| {
+///         let value = value.ok_or(ParseError::InvalidOptionValue(lineno))?;
+///         extensions.insert("retrans".into(), value.into());
+///         Ok(())
+///     });
+///
+/// let config = parser.parse("nameserver 10.0.0.1\nport 5353\noptions retrans:3").unwrap();
+/// assert_eq!(config.extensions["port"], "5353");
+/// assert_eq!(config.extensions["retrans"], "3");
+/// assert!(parser.parse("port http").is_err());
+/// # }
+/// ```
+#[derive(Default)]
+pub struct Parser {
+    directives: BTreeMap<String, Box<DirectiveHandler>>,
+    options: BTreeMap<String, Box<OptionHandler>>,
+}
+
+impl Parser {
+    /// Create a parser that only knows the built-in directives and options,
+    /// like [`Config::parse`].
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Handle the directive `keyword` with `handler`, replacing any previous
+    /// handler for it.
+    pub fn directive<F>(mut self, keyword: &str, handler: F) -> Self
+    where
+        F: Fn(usize, &str, &mut BTreeMap<String, String>) -> Result<(), ParseError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.directives
+            .insert(keyword.to_owned(), Box::new(handler));
+        self
+    }
+
+    /// Handle the option `name` of `options` directives with `handler`,
+    /// replacing any previous handler for it.
+    pub fn option<F>(mut self, name: &str, handler: F) -> Self
+    where
+        F: Fn(usize, Option<&str>, &mut BTreeMap<String, String>) -> Result<(), ParseError>
+            + Send
+            + Sync
+            + 'static,
+    {
+        self.options.insert(name.to_owned(), Box::new(handler));
+        self
+    }
+
+    /// Parse a buffer and return the corresponding `Config` object, failing
+    /// on the first error.
+    pub fn parse<T: AsRef<[u8]>>(&self, buf: T) -> Result<Config, ParseError> {
+        let (config, errors) = self.parse_with_errors(buf.as_ref());
+        match errors.into_iter().next() {
+            Some(err) => Err(err),
+            None => Ok(config),
+        }
+    }
+
+    /// Parse a buffer and return a best-effort parsed `Config` object along
+    /// with any errors.
+    pub fn parse_with_errors(&self, bytes: &[u8]) -> (Config, Vec<ParseError>) {
+        let mut config = Config::new();
+        let mut errors = Vec::new();
+        grammar::visit(
+            bytes,
+            &mut ConfigVisitor::new(&mut config, &mut errors).with_parser(self),
+        );
+        (config, errors)
+    }
+
+    pub(crate) fn directive_handler(&self, keyword: &str) -> Option<&DirectiveHandler> {
+        self.directives.get(keyword).map(|handler| &**handler)
+    }
+
+    pub(crate) fn option_handler(&self, name: &str) -> Option<&OptionHandler> {
+        self.options.get(name).map(|handler| &**handler)
+    }
+}
+
+impl fmt::Debug for Parser {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.debug_struct("Parser")
+            .field("directives", &self.directives.keys().collect::<Vec<_>>())
+            .field("options", &self.options.keys().collect::<Vec<_>>())
+            .finish()
+    }
+}
@@ -0,0 +1,199 @@
+use std::fmt;
+use std::io::BufRead;
+
+use crate::{Config, LoadError, ParseError};
+
+/// Resource limits enforced by [`Config::parse_reader`].
+///
+/// The defaults are far above anything a real resolv.conf needs, while
+/// keeping the memory used by a hostile file small.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ParseLimits {
+    /// Maximum size of the whole input, in bytes
+    pub max_bytes: u64,
+    /// Maximum length of a line, in bytes, not counting the newline
+    pub max_line_length: usize,
+    /// Maximum number of lines
+    pub max_lines: usize,
+    /// Maximum number of nameservers, over all `nameserver` lines
+    pub max_nameservers: usize,
+    /// Maximum number of domains in a `search` line
+    pub max_search: usize,
+    /// Maximum number of networks in a `sortlist` line
+    pub max_sortlist: usize,
+}
+
+impl Default for ParseLimits {
+    fn default() -> Self {
+        Self {
+            max_bytes: 1024 * 1024,
+            max_line_length: 4096,
+            max_lines: 10_000,
+            max_nameservers: 64,
+            max_search: 64,
+            max_sortlist: 64,
+        }
+    }
+}
+
+/// The limit of [`ParseLimits`] a file exceeded.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum Limit {
+    /// [`ParseLimits::max_bytes`]
+    Bytes,
+    /// [`ParseLimits::max_line_length`]
+    LineLength,
+    /// [`ParseLimits::max_lines`]
+    Lines,
+    /// [`ParseLimits::max_nameservers`]
+    Nameservers,
+    /// [`ParseLimits::max_search`]
+    Search,
+    /// [`ParseLimits::max_sortlist`]
+    Sortlist,
+}
+
+impl fmt::Display for Limit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            Self::Bytes => "size limit",
+            Self::LineLength => "line length limit",
+            Self::Lines => "line count limit",
+            Self::Nameservers => "nameserver limit",
+            Self::Search => "search domain limit",
+            Self::Sortlist => "sortlist limit",
+        })
+    }
+}
+
+impl Config {
+    /// Parse a configuration from `reader` line by line, within `limits`.
+    ///
+    /// Only one line is held in memory at a time, and reading stops as soon
+    /// as a limit is exceeded, with a [`ParseError::LimitExceeded`] error.
+    /// Other errors are handled like [`parse`](#method.parse) does.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::{Config, Limit, LoadError, ParseError, ParseLimits};
+    /// # fn main() {
+    /// let limits = ParseLimits::default();
+    /// let config = Config::parse_reader(&b"nameserver 10.0.0.1\n"[..], &limits).unwrap();
+    /// assert_eq!(config.nameservers.len(), 1);
+    ///
+    /// let limits = ParseLimits { max_line_length: 16, ..ParseLimits::default() };
+    /// match Config::parse_reader(&b"search a.example b.example\n"[..], &limits) {
+    ///     Err(LoadError::Parse(_, ParseError::LimitExceeded(0, Limit::LineLength))) => {}
+    ///     other => panic!("unexpected result: {:?}", other),
+    /// }
+    /// # }
+    /// ```
+    pub fn parse_reader<R: BufRead>(reader: R, limits: &ParseLimits) -> Result<Self, LoadError> {
+        let (config, errors) = Self::parse_reader_with_errors(reader, limits)?;
+        match errors.into_iter().next() {
+            Some(err) => Err(LoadError::Parse(None, err)),
+            None => Ok(config),
+        }
+    }
+
+    /// Parse a configuration from `reader` line by line, within `limits`,
+    /// and return it along with the errors of the lines that could not be
+    /// parsed, like [`parse_with_errors`](#method.parse_with_errors).
+    ///
+    /// Reading stops at the line exceeding a limit, with a
+    /// [`ParseError::LimitExceeded`] as the last error, and the
+    /// configuration holds what was parsed until then. Only a failure to
+    /// read is returned as an `Err`.
+    pub fn parse_reader_with_errors<R: BufRead>(
+        mut reader: R,
+        limits: &ParseLimits,
+    ) -> Result<(Self, Vec<ParseError>), LoadError> {
+        let mut cfg = Self::new();
+        let mut errors = Vec::new();
+        let mut line = Vec::new();
+        let mut total = 0u64;
+        let mut lineno = 0;
+        loop {
+            line.clear();
+            let (read, complete) = read_line(
+                &mut reader,
+                &mut line,
+                limits.max_line_length.saturating_add(1),
+            )
+            .map_err(|e| LoadError::Io(None, e))?;
+            // Like `split`, the text after the last newline is a line, but
+            // an empty one changes nothing and does not count
+            if read == 0 && !complete {
+                break;
+            }
+            total += read as u64;
+            let exceeded = if total > limits.max_bytes {
+                Some(Limit::Bytes)
+            } else if line.len() > limits.max_line_length {
+                Some(Limit::LineLength)
+            } else if lineno >= limits.max_lines {
+                Some(Limit::Lines)
+            } else {
+                None
+            };
+            if let Some(exceeded) = exceeded {
+                errors.push(ParseError::LimitExceeded(lineno, exceeded));
+                break;
+            }
+
+            cfg.parse_line(lineno, &line, &mut errors);
+            let exceeded = if cfg.nameservers.len() > limits.max_nameservers {
+                Some(Limit::Nameservers)
+            } else if cfg.get_search().map_or(0, Vec::len) > limits.max_search {
+                Some(Limit::Search)
+            } else if cfg.sortlist.len() > limits.max_sortlist {
+                Some(Limit::Sortlist)
+            } else {
+                None
+            };
+            if let Some(exceeded) = exceeded {
+                errors.push(ParseError::LimitExceeded(lineno, exceeded));
+                break;
+            }
+
+            if !complete {
+                break;
+            }
+            lineno += 1;
+        }
+        Ok((cfg, errors))
+    }
+}
+
+/// Append the next line to `line`, without its newline, reading at most
+/// `max` bytes of it. Return the number of bytes consumed and whether a
+/// newline ended the line.
+fn read_line<R: BufRead>(
+    reader: &mut R,
+    line: &mut Vec<u8>,
+    max: usize,
+) -> std::io::Result<(usize, bool)> {
+    let mut read = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok((read, false));
+        }
+        let (used, complete) = match available.iter().position(|&c| c == b'\n') {
+            Some(pos) => (pos + 1, true),
+            None => (available.len(), false),
+        };
+        let text = &available[..used - complete as usize];
+        let room = max.saturating_sub(line.len());
+        line.extend_from_slice(&text[..text.len().min(room)]);
+        reader.consume(used);
+        read += used;
+        if complete || line.len() >= max {
+            return Ok((read, complete));
+        }
+    }
+}
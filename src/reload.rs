@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant, SystemTime};
+
+use crate::{Config, LoadError};
+
+/// A shared handle on a resolv.conf file that is re-read when it changes.
+///
+/// This mirrors the check glibc does in `res_init`: the file is stat'ed at
+/// most once per [`interval`](#method.with_interval), and parsed again when
+/// its modification time, size or inode changed. Once a configuration with
+/// `options no-reload` has been loaded, the file is not watched anymore.
+///
+/// If the new file can not be read or parsed, the last good configuration
+/// is kept and the error is available from [`last_error`](#method.last_error).
+///
+/// The handle is cheap to clone, and clones share the same state.
+///
+/// ```rust,no_run
+/// # extern crate resolv_conf;
+/// use resolv_conf::SystemConfig;
+/// # fn main() {
+/// let system = SystemConfig::open("/etc/resolv.conf").unwrap();
+/// let config = system.current();
+/// println!("{:?}", config.nameservers);
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct SystemConfig {
+    inner: Arc<Mutex<State>>,
+}
+
+#[derive(Debug)]
+struct State {
+    path: PathBuf,
+    interval: Duration,
+    config: Arc<Config>,
+    stamp: Option<Stamp>,
+    /// When the file was last checked, `None` to check it right away
+    checked: Option<Instant>,
+    error: Option<Arc<LoadError>>,
+}
+
+/// The attributes of the file a change is detected from
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct Stamp {
+    modified: Option<SystemTime>,
+    len: u64,
+    inode: u64,
+}
+
+impl SystemConfig {
+    /// Load the file at `path` and return a handle on it.
+    ///
+    /// Fails if the file can not be read or parsed; use
+    /// [`with_config`](#method.with_config) to start from a fallback
+    /// configuration instead.
+    pub fn open<P: AsRef<Path>>(path: P) -> Result<Self, LoadError> {
+        let path = path.as_ref();
+        let stamp = stamp(path)?;
+        let config = Config::from_path(path)?;
+        Ok(Self::from_state(path, config, Some(stamp)))
+    }
+
+    /// Return a handle on the file at `path` that starts with `config`. The
+    /// file is read on the first call to [`current`](#method.current).
+    pub fn with_config<P: AsRef<Path>>(path: P, config: Config) -> Self {
+        Self::from_state(path.as_ref(), config, None)
+    }
+
+    fn from_state(path: &Path, config: Config, stamp: Option<Stamp>) -> Self {
+        let checked = stamp.as_ref().map(|_| Instant::now());
+        Self {
+            inner: Arc::new(Mutex::new(State {
+                path: path.to_owned(),
+                interval: Duration::from_secs(5),
+                config: Arc::new(config),
+                stamp,
+                checked,
+                error: None,
+            })),
+        }
+    }
+
+    /// Set the minimal interval between two checks of the file (5 seconds
+    /// by default). A zero interval checks the file on every call.
+    pub fn with_interval(self, interval: Duration) -> Self {
+        self.lock().interval = interval;
+        self
+    }
+
+    /// Return the current configuration, re-reading the file if it changed.
+    pub fn current(&self) -> Arc<Config> {
+        let mut state = self.lock();
+        let now = Instant::now();
+        let recent = match state.checked {
+            Some(checked) => now.duration_since(checked) < state.interval,
+            None => false,
+        };
+        if state.config.no_reload || recent {
+            return state.config.clone();
+        }
+        state.checked = Some(now);
+
+        let path = state.path.clone();
+        let new_stamp = match stamp(&path) {
+            Ok(stamp) => stamp,
+            Err(e) => {
+                state.error = Some(Arc::new(e));
+                return state.config.clone();
+            }
+        };
+        if state.stamp.as_ref() == Some(&new_stamp) {
+            return state.config.clone();
+        }
+
+        match Config::from_path(&path) {
+            Ok(config) => {
+                state.config = Arc::new(config);
+                state.stamp = Some(new_stamp);
+                state.error = None;
+            }
+            Err(e) => state.error = Some(Arc::new(e)),
+        }
+        state.config.clone()
+    }
+
+    /// Return the configuration without checking the file.
+    pub fn cached(&self) -> Arc<Config> {
+        self.lock().config.clone()
+    }
+
+    /// Whether the file is still watched, that is the loaded configuration
+    /// does not have `options no-reload`.
+    pub fn is_watching(&self) -> bool {
+        !self.lock().config.no_reload
+    }
+
+    /// Return the error of the last failed reload, if the last reload
+    /// attempt failed.
+    pub fn last_error(&self) -> Option<Arc<LoadError>> {
+        self.lock().error.clone()
+    }
+
+    /// Return the path of the file.
+    pub fn path(&self) -> PathBuf {
+        self.lock().path.clone()
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, State> {
+        // The state is always consistent, even if a holder panicked
+        match self.inner.lock() {
+            Ok(guard) => guard,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+fn stamp(path: &Path) -> Result<Stamp, LoadError> {
+    let metadata = fs::metadata(path).map_err(|e| LoadError::from_io(path, e))?;
+    #[cfg(unix)]
+    let inode = std::os::unix::fs::MetadataExt::ino(&metadata);
+    #[cfg(not(unix))]
+    let inode = 0;
+    Ok(Stamp {
+        modified: metadata.modified().ok(),
+        len: metadata.len(),
+        inode,
+    })
+}
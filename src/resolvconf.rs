@@ -0,0 +1,532 @@
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::{Config, ParseError, ScopedIp};
+
+/// Configuration directory of Debian's `resolvconf`
+pub const RESOLVCONF_DIR: &str = "/etc/resolvconf";
+/// Directory holding the per-interface records of Debian's `resolvconf`
+pub const RESOLVCONF_INTERFACE_DIR: &str = "/run/resolvconf/interface";
+/// Configuration file of openresolv
+pub const OPENRESOLV_CONF: &str = "/etc/resolvconf.conf";
+/// Directory holding the per-interface records of openresolv
+pub const OPENRESOLV_INTERFACE_DIR: &str = "/run/resolvconf/interfaces";
+
+/// The order of interfaces used by Debian's `resolvconf` when
+/// `/etc/resolvconf/interface-order` does not exist
+pub const DEFAULT_INTERFACE_ORDER: &[&str] = &[
+    "lo.inet6",
+    "lo.inet",
+    "lo.@(dnsmasq|pdnsd)",
+    "lo.!(pdns|pdns-recursor)",
+    "lo",
+    "tun*",
+    "tap*",
+    "hso*",
+    "em+([0-9])?(_+([0-9]))*",
+    "p+([0-9])p+([0-9])*",
+    "eth*",
+    "ath*",
+    "wlan*",
+    "ppp*",
+    "*",
+];
+
+/// The inputs `resolvconf` (Debian) or openresolv assemble `/etc/resolv.conf`
+/// from.
+///
+/// [`generate`](#method.generate) reproduces the merge done by the `libc`
+/// update script, so the generated file can be predicted offline:
+///
+/// 1. the head file is copied verbatim,
+/// 2. the nameservers of the base file and of the interface records are
+///    listed, in interface order and without duplicates,
+/// 3. their `domain` and `search` entries are merged into one `search` line,
+/// 4. every other line (`options`, `sortlist`...) is copied,
+/// 5. the tail file is copied verbatim.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::Resolvconf;
+/// # fn main() {
+/// let mut resolvconf = Resolvconf::debian();
+/// resolvconf.interfaces.push(("eth0.dhclient".into(), "nameserver 192.168.1.1\nsearch home".into()));
+/// resolvconf.interfaces.push(("tun0".into(), "nameserver 10.1.1.1\nsearch corp.example.com".into()));
+///
+/// let output = resolvconf.generate();
+/// assert_eq!(output.text, "nameserver 10.1.1.1\nnameserver 192.168.1.1\nsearch corp.example.com home\n");
+/// assert_eq!(output.nameserver_sources[0].1, "tun0");
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Resolvconf {
+    /// Text copied at the top of the generated file
+    pub head: Option<String>,
+    /// Record processed before every interface record
+    pub base: Option<String>,
+    /// Text copied at the bottom of the generated file
+    pub tail: Option<String>,
+    /// Interface records as `(name, contents)` pairs, in any order
+    pub interfaces: Vec<(String, String)>,
+    /// Glob patterns ordering the interface records. Records matching no
+    /// pattern are left out, unless `include_unmatched` is set.
+    pub interface_order: Vec<String>,
+    /// Whether records matching no pattern are kept, sorted by name, after
+    /// the ones that do
+    pub include_unmatched: bool,
+    /// Glob patterns of interfaces whose nameservers are not listed
+    pub private_interfaces: Vec<String>,
+    /// Nameservers listed before the ones of the records
+    pub prepend_nameservers: Vec<String>,
+    /// Nameservers listed after the ones of the records
+    pub append_nameservers: Vec<String>,
+    /// Search domains listed before the ones of the records
+    pub prepend_search: Vec<String>,
+    /// Search domains listed after the ones of the records
+    pub append_search: Vec<String>,
+    /// Maximum number of nameservers written, if any
+    pub max_nameservers: Option<usize>,
+    /// Whether no more nameservers are written after a loopback one, like
+    /// Debian's `TRUNCATE_NAMESERVER_LIST_AFTER_LOOPBACK_ADDRESS`
+    pub truncate_after_loopback: bool,
+}
+
+/// The file generated by [`Resolvconf::generate`].
+#[derive(Debug)]
+pub struct ResolvconfOutput {
+    /// The text of the generated file
+    pub text: String,
+    /// The generated file, parsed
+    pub config: Config,
+    /// Each nameserver of `config`, with the name of the record that
+    /// contributed it: an interface name, `base` or `resolvconf.conf`
+    pub nameserver_sources: Vec<(ScopedIp, String)>,
+    /// Errors found while parsing the generated file
+    pub errors: Vec<ParseError>,
+}
+
+impl Resolvconf {
+    /// Create an empty set of inputs with the behavior of Debian's
+    /// `resolvconf`.
+    pub fn debian() -> Self {
+        Self {
+            interface_order: DEFAULT_INTERFACE_ORDER
+                .iter()
+                .map(|p| p.to_string())
+                .collect(),
+            max_nameservers: Some(3),
+            truncate_after_loopback: true,
+            ..Self::default()
+        }
+    }
+
+    /// Create an empty set of inputs with the behavior of openresolv.
+    pub fn openresolv() -> Self {
+        Self {
+            interface_order: ["lo", "lo[0-9]*"].iter().map(|p| p.to_string()).collect(),
+            include_unmatched: true,
+            ..Self::default()
+        }
+    }
+
+    /// Load the inputs of Debian's `resolvconf` from its default locations.
+    pub fn load_debian() -> Result<Self, ResolvconfError> {
+        Self::load_debian_from(
+            Path::new(RESOLVCONF_DIR),
+            Path::new(RESOLVCONF_INTERFACE_DIR),
+        )
+    }
+
+    /// Load the inputs of Debian's `resolvconf` from the given configuration
+    /// directory (normally `/etc/resolvconf`) and interface directory.
+    ///
+    /// This reads `resolv.conf.d/{head,base,tail}` and `interface-order`
+    /// from `conf_dir`, any of which may be missing, and every non-empty
+    /// record of `interface_dir`.
+    pub fn load_debian_from(
+        conf_dir: &Path,
+        interface_dir: &Path,
+    ) -> Result<Self, ResolvconfError> {
+        let mut resolvconf = Self::debian();
+        let parts = conf_dir.join("resolv.conf.d");
+        resolvconf.head = read_optional(&parts.join("head"))?;
+        resolvconf.base = read_optional(&parts.join("base"))?;
+        resolvconf.tail = read_optional(&parts.join("tail"))?;
+        if let Some(order) = read_optional(&conf_dir.join("interface-order"))? {
+            resolvconf.interface_order = order
+                .lines()
+                .map(str::trim)
+                .filter(|line| !line.is_empty() && !line.starts_with('#'))
+                .map(str::to_owned)
+                .collect();
+        }
+        resolvconf.interfaces = read_interfaces(interface_dir)?;
+        Ok(resolvconf)
+    }
+
+    /// Load the inputs of openresolv from its default locations.
+    pub fn load_openresolv() -> Result<Self, ResolvconfError> {
+        Self::load_openresolv_from(
+            Path::new(OPENRESOLV_CONF),
+            Path::new(OPENRESOLV_INTERFACE_DIR),
+        )
+    }
+
+    /// Load the inputs of openresolv from the given `resolvconf.conf` and
+    /// interface directory, either of which may be missing.
+    ///
+    /// The following variables of `resolvconf.conf` are understood:
+    /// `name_servers`, `name_servers_append`, `search_domains`,
+    /// `search_domains_append`, `interface_order`, `dynamic_order`,
+    /// `private_interfaces`, `resolv_conf_head` and `resolv_conf_tail`.
+    pub fn load_openresolv_from(
+        conf: &Path,
+        interface_dir: &Path,
+    ) -> Result<Self, ResolvconfError> {
+        let mut resolvconf = Self::openresolv();
+        let mut dynamic_order = [
+            "tap[0-9]*",
+            "tun[0-9]*",
+            "vpn",
+            "vpn[0-9]*",
+            "ppp[0-9]*",
+            "ippp[0-9]*",
+        ]
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>();
+        let text = read_optional(conf)?.unwrap_or_default();
+        for (key, value) in shell_assignments(&text) {
+            let words = || {
+                value
+                    .split_whitespace()
+                    .map(str::to_owned)
+                    .collect::<Vec<_>>()
+            };
+            match key {
+                "name_servers" => resolvconf.prepend_nameservers = words(),
+                "name_servers_append" => resolvconf.append_nameservers = words(),
+                "search_domains" => resolvconf.prepend_search = words(),
+                "search_domains_append" => resolvconf.append_search = words(),
+                "interface_order" => resolvconf.interface_order = words(),
+                "dynamic_order" => dynamic_order = words(),
+                "private_interfaces" => resolvconf.private_interfaces = words(),
+                "resolv_conf_head" => resolvconf.head = Some(format!("{value}\n")),
+                "resolv_conf_tail" => resolvconf.tail = Some(format!("{value}\n")),
+                _ => {}
+            }
+        }
+        resolvconf.interface_order.extend(dynamic_order);
+        resolvconf.interfaces = read_interfaces(interface_dir)?;
+        Ok(resolvconf)
+    }
+
+    /// Return the names of the interface records in the order they are
+    /// merged.
+    pub fn ordered_interfaces(&self) -> Vec<&str> {
+        let mut names = self
+            .interfaces
+            .iter()
+            .map(|(name, _)| name.as_str())
+            .collect::<Vec<_>>();
+        names.sort_unstable();
+        names.dedup();
+
+        let mut ordered = Vec::with_capacity(names.len());
+        for pattern in &self.interface_order {
+            for name in &names {
+                if !ordered.contains(name) && glob_match(pattern, name) {
+                    ordered.push(*name);
+                }
+            }
+        }
+        if self.include_unmatched {
+            for name in names {
+                if !ordered.contains(&name) {
+                    ordered.push(name);
+                }
+            }
+        }
+        ordered
+    }
+
+    /// Assemble the resolv.conf file.
+    pub fn generate(&self) -> ResolvconfOutput {
+        let mut nameservers: Vec<(&str, &str)> = Vec::new();
+        let mut search: Vec<&str> = Vec::new();
+        let mut other: Vec<&str> = Vec::new();
+
+        for word in &self.prepend_nameservers {
+            nameservers.push((word, "resolvconf.conf"));
+        }
+        search.extend(self.prepend_search.iter().map(String::as_str));
+
+        let mut records = Vec::new();
+        if let Some(base) = &self.base {
+            records.push(("base", base.as_str()));
+        }
+        for name in self.ordered_interfaces() {
+            for (_, contents) in self.interfaces.iter().filter(|(n, _)| n == name) {
+                records.push((name, contents));
+            }
+        }
+
+        for (name, contents) in records {
+            let private = self.private_interfaces.iter().any(|p| glob_match(p, name));
+            for line in contents.lines() {
+                let text = line.split(['#', ';']).next().unwrap_or("").trim();
+                let mut words = text.split_whitespace();
+                match words.next() {
+                    None => {}
+                    Some("nameserver") => {
+                        if let (Some(word), false) = (words.next(), private) {
+                            nameservers.push((word, name));
+                        }
+                    }
+                    Some("domain") | Some("search") => search.extend(words),
+                    Some(_) => other.push(text),
+                }
+            }
+        }
+
+        for word in &self.append_nameservers {
+            nameservers.push((word, "resolvconf.conf"));
+        }
+        search.extend(self.append_search.iter().map(String::as_str));
+
+        let mut seen = Vec::new();
+        let mut nameserver_lines = Vec::new();
+        let mut nameserver_sources = Vec::new();
+        for (word, source) in nameservers {
+            if seen.contains(&word) || self.max_nameservers.map_or(false, |max| seen.len() >= max) {
+                continue;
+            }
+            seen.push(word);
+            nameserver_lines.push(word);
+            let ip = ScopedIp::from_str(word).ok();
+            if let Some(ip) = &ip {
+                nameserver_sources.push((ip.clone(), source.to_owned()));
+            }
+            if self.truncate_after_loopback && ip.map_or(false, |ip| is_loopback(&ip)) {
+                break;
+            }
+        }
+        let mut unique_search = Vec::new();
+        for domain in search {
+            if !unique_search.contains(&domain) {
+                unique_search.push(domain);
+            }
+        }
+
+        let mut text = String::new();
+        if let Some(head) = &self.head {
+            text.push_str(head);
+        }
+        for word in nameserver_lines {
+            text.push_str(&format!("nameserver {word}\n"));
+        }
+        if !unique_search.is_empty() {
+            text.push_str(&format!("search {}\n", unique_search.join(" ")));
+        }
+        for line in other {
+            text.push_str(line);
+            text.push('\n');
+        }
+        if let Some(tail) = &self.tail {
+            text.push_str(tail);
+        }
+
+        let (config, errors) = Config::parse_with_errors(text.as_bytes());
+        ResolvconfOutput {
+            text,
+            config,
+            nameserver_sources,
+            errors,
+        }
+    }
+}
+
+fn is_loopback(ip: &ScopedIp) -> bool {
+    match ip {
+        ScopedIp::V4(ip) => ip.is_loopback(),
+        ScopedIp::V6(ip, _) => ip.is_loopback(),
+    }
+}
+
+fn read_optional(path: &Path) -> Result<Option<String>, ResolvconfError> {
+    match fs::read_to_string(path) {
+        Ok(text) => Ok(Some(text)),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(e) => Err(ResolvconfError(path.to_owned(), e)),
+    }
+}
+
+fn read_interfaces(dir: &Path) -> Result<Vec<(String, String)>, ResolvconfError> {
+    let entries = match fs::read_dir(dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(ResolvconfError(dir.to_owned(), e)),
+    };
+    let mut interfaces = Vec::new();
+    for entry in entries {
+        let path = entry
+            .map_err(|e| ResolvconfError(dir.to_owned(), e))?
+            .path();
+        let name = match path.file_name().and_then(|name| name.to_str()) {
+            Some(name) if !name.starts_with('.') => name.to_owned(),
+            _ => continue,
+        };
+        let contents = fs::read_to_string(&path).map_err(|e| ResolvconfError(path.clone(), e))?;
+        if !contents.trim().is_empty() {
+            interfaces.push((name, contents));
+        }
+    }
+    interfaces.sort();
+    Ok(interfaces)
+}
+
+/// Extract the `key=value` and `key="value"` assignments of a shell script
+fn shell_assignments(text: &str) -> impl Iterator<Item = (&str, &str)> {
+    text.lines().filter_map(|line| {
+        let line = line.trim();
+        if line.starts_with('#') {
+            return None;
+        }
+        let (key, value) = line.split_once('=')?;
+        let value = value.trim();
+        let value = match (value.strip_prefix('"'), value.strip_prefix('\'')) {
+            (Some(value), _) => value.strip_suffix('"')?,
+            (_, Some(value)) => value.strip_suffix('\'')?,
+            _ => value,
+        };
+        Some((key.trim(), value))
+    })
+}
+
+/// Match `name` against a bash glob, including the `extglob` patterns used
+/// by the default `interface-order` of Debian.
+pub(crate) fn glob_match(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    match_at(&pattern, &name)
+}
+
+fn match_at(p: &[char], n: &[char]) -> bool {
+    match p {
+        [] => n.is_empty(),
+        [op @ ('@' | '?' | '*' | '+' | '!'), '(', rest @ ..] => match split_group(rest) {
+            Some((alternatives, rest)) => {
+                let one = |s: &[char]| alternatives.iter().any(|alt| match_at(alt, s));
+                (0..=n.len()).any(|k| {
+                    let (head, tail) = n.split_at(k);
+                    let head_matches = match op {
+                        '@' => one(head),
+                        '?' => head.is_empty() || one(head),
+                        '*' => repeat(&one, head),
+                        '+' => !head.is_empty() && repeat(&one, head),
+                        _ => !one(head),
+                    };
+                    head_matches && match_at(rest, tail)
+                })
+            }
+            None => literal(p, n),
+        },
+        ['*', rest @ ..] => (0..=n.len()).any(|k| match_at(rest, &n[k..])),
+        ['?', rest @ ..] => !n.is_empty() && match_at(rest, &n[1..]),
+        ['[', rest @ ..] => match (class_end(rest), n.first()) {
+            (Some(end), Some(&c)) => {
+                let (negate, class) = match rest[0] {
+                    '!' | '^' => (true, &rest[1..end]),
+                    _ => (false, &rest[..end]),
+                };
+                class_contains(class, c) != negate && match_at(&rest[end + 1..], &n[1..])
+            }
+            (Some(_), None) => false,
+            (None, _) => literal(p, n),
+        },
+        ['\\', c, rest @ ..] => n.first() == Some(c) && match_at(rest, &n[1..]),
+        _ => literal(p, n),
+    }
+}
+
+fn literal(p: &[char], n: &[char]) -> bool {
+    n.first() == p.first() && match_at(&p[1..], &n[1..])
+}
+
+/// Whether `s` is made of zero or more substrings matching `one`
+fn repeat(one: &dyn Fn(&[char]) -> bool, s: &[char]) -> bool {
+    s.is_empty() || (1..=s.len()).any(|k| one(&s[..k]) && repeat(one, &s[k..]))
+}
+
+/// Split `a|b)rest` into `([a, b], rest)`
+fn split_group(p: &[char]) -> Option<(Vec<&[char]>, &[char])> {
+    let mut depth = 0;
+    let mut start = 0;
+    let mut alternatives = Vec::new();
+    for (i, &c) in p.iter().enumerate() {
+        match c {
+            '(' => depth += 1,
+            ')' if depth > 0 => depth -= 1,
+            ')' => {
+                alternatives.push(&p[start..i]);
+                return Some((alternatives, &p[i + 1..]));
+            }
+            '|' if depth == 0 => {
+                alternatives.push(&p[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Return the index of the `]` closing a character class
+fn class_end(p: &[char]) -> Option<usize> {
+    let skip = match p.first() {
+        Some('!') | Some('^') => 2,
+        _ => 1,
+    };
+    p.iter()
+        .skip(skip)
+        .position(|&c| c == ']')
+        .map(|i| i + skip)
+}
+
+fn class_contains(class: &[char], c: char) -> bool {
+    let mut i = 0;
+    while i < class.len() {
+        if i + 2 < class.len() && class[i + 1] == '-' {
+            if class[i] <= c && c <= class[i + 2] {
+                return true;
+            }
+            i += 3;
+        } else {
+            if class[i] == c {
+                return true;
+            }
+            i += 1;
+        }
+    }
+    false
+}
+
+/// Error while reading the inputs of `resolvconf` or openresolv
+#[derive(Debug)]
+pub struct ResolvconfError(pub PathBuf, pub io::Error);
+
+impl fmt::Display for ResolvconfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "failed to read {}: {}", self.0.display(), self.1)
+    }
+}
+
+impl Error for ResolvconfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.1)
+    }
+}
@@ -0,0 +1,185 @@
+use std::fs;
+use std::net::Ipv4Addr;
+use std::path::{Path, PathBuf};
+
+use crate::load::load_path;
+use crate::{Config, LoadError, ScopedIp};
+
+/// Path of the stub-mode file written by systemd-resolved
+pub const STUB_RESOLV_CONF: &str = "/run/systemd/resolve/stub-resolv.conf";
+/// Path of the static stub file shipped with systemd
+pub const STATIC_RESOLV_CONF: &str = "/usr/lib/systemd/resolv.conf";
+/// Path of the file listing the uplink servers known to systemd-resolved
+pub const UPLINK_RESOLV_CONF: &str = "/run/systemd/resolve/resolv.conf";
+
+/// Address of the main DNS stub listener of systemd-resolved
+pub const STUB_ADDRESS: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 53);
+/// Address of the DNS proxy stub listener of systemd-resolved
+pub const PROXY_STUB_ADDRESS: Ipv4Addr = Ipv4Addr::new(127, 0, 0, 54);
+
+/// The way `/etc/resolv.conf` is handled on a system running systemd-resolved.
+///
+/// See the "/ETC/RESOLV.CONF" section of `man 8 systemd-resolved`.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ResolvedMode {
+    /// Queries go to the local stub listener and the file lists the
+    /// configured search domains
+    Stub,
+    /// Queries go to the local stub listener and the file has no search
+    /// domains
+    Static,
+    /// The file lists the uplink servers directly, bypassing the stub
+    Uplink,
+    /// The file is not managed by systemd-resolved
+    Foreign,
+}
+
+/// What gave away the [`ResolvedMode`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ResolvedEvidence {
+    /// `/etc/resolv.conf` is a symlink to the given target
+    Symlink(PathBuf),
+    /// The file starts with the given header comment
+    Header(String),
+    /// Every nameserver in the file is a stub listener address
+    StubNameserver,
+    /// Nothing points to systemd-resolved
+    None,
+}
+
+/// Paths consulted by [`ResolvedConfig::load_from`].
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedPaths {
+    /// The file applications read, normally `/etc/resolv.conf`
+    pub resolv_conf: PathBuf,
+    /// The file listing the uplink servers, normally
+    /// `/run/systemd/resolve/resolv.conf`
+    pub uplink: PathBuf,
+}
+
+impl Default for ResolvedPaths {
+    fn default() -> Self {
+        Self {
+            resolv_conf: PathBuf::from("/etc/resolv.conf"),
+            uplink: PathBuf::from(UPLINK_RESOLV_CONF),
+        }
+    }
+}
+
+/// The resolver configuration of a system that may run systemd-resolved.
+///
+/// ```rust,no_run
+/// # extern crate resolv_conf;
+/// use resolv_conf::ResolvedConfig;
+/// # fn main() {
+/// let resolved = ResolvedConfig::load().unwrap();
+/// println!("mode: {:?}", resolved.mode);
+/// println!("upstreams: {:?}", resolved.effective().nameservers);
+/// # }
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedConfig {
+    /// The detected mode
+    pub mode: ResolvedMode,
+    /// Why this mode was chosen
+    pub evidence: ResolvedEvidence,
+    /// The configuration applications see
+    pub config: Config,
+    /// The uplink configuration of systemd-resolved. It is only loaded in the
+    /// `Stub` and `Static` modes, and is `None` if the file does not exist.
+    pub upstream: Option<Config>,
+}
+
+impl ResolvedConfig {
+    /// Load the configuration from the default paths.
+    pub fn load() -> Result<Self, LoadError> {
+        Self::load_from(&ResolvedPaths::default())
+    }
+
+    /// Load the configuration from the given paths.
+    pub fn load_from(paths: &ResolvedPaths) -> Result<Self, LoadError> {
+        let (config, buf) = load_path(&paths.resolv_conf)?;
+        let target = fs::read_link(&paths.resolv_conf).ok();
+        let (mode, evidence) = detect(target.as_deref(), &buf, &config);
+
+        let upstream = match mode {
+            ResolvedMode::Stub | ResolvedMode::Static => match Config::from_path(&paths.uplink) {
+                Ok(config) => Some(config),
+                Err(LoadError::NotFound(_)) => None,
+                Err(e) => return Err(e),
+            },
+            ResolvedMode::Uplink | ResolvedMode::Foreign => None,
+        };
+
+        Ok(Self {
+            mode,
+            evidence,
+            config,
+            upstream,
+        })
+    }
+
+    /// Return the configuration listing the real upstream servers.
+    ///
+    /// This is the uplink configuration when the stub is in use and it is
+    /// known, and the configuration applications see otherwise.
+    pub fn effective(&self) -> &Config {
+        self.upstream.as_ref().unwrap_or(&self.config)
+    }
+}
+
+/// Detect the mode from the symlink target, the header comment and the
+/// nameservers, in that order.
+fn detect(target: Option<&Path>, buf: &[u8], config: &Config) -> (ResolvedMode, ResolvedEvidence) {
+    if let Some(target) = target {
+        if let Some(mode) = mode_from_path(&target.to_string_lossy()) {
+            return (mode, ResolvedEvidence::Symlink(target.to_owned()));
+        }
+    }
+
+    for comment in comment_lines(buf) {
+        if let Some(path) = comment.strip_prefix("This is ") {
+            if let Some(mode) = mode_from_path(path.split(' ').next().unwrap_or("")) {
+                return (mode, ResolvedEvidence::Header(comment.to_owned()));
+            }
+        }
+    }
+
+    let is_stub = |ip: &ScopedIp| match ip {
+        ScopedIp::V4(ip) => *ip == STUB_ADDRESS || *ip == PROXY_STUB_ADDRESS,
+        ScopedIp::V6(..) => false,
+    };
+    if !config.nameservers.is_empty() && config.nameservers.iter().all(is_stub) {
+        let mode = match config.get_search() {
+            Some(_) => ResolvedMode::Stub,
+            None => ResolvedMode::Static,
+        };
+        return (mode, ResolvedEvidence::StubNameserver);
+    }
+
+    (ResolvedMode::Foreign, ResolvedEvidence::None)
+}
+
+/// Symlinks may be relative, so only the well-known suffixes are compared.
+fn mode_from_path(path: &str) -> Option<ResolvedMode> {
+    if path.ends_with("systemd/resolve/stub-resolv.conf") {
+        Some(ResolvedMode::Stub)
+    } else if path.ends_with("systemd/resolve/resolv.conf") {
+        Some(ResolvedMode::Uplink)
+    } else if path.ends_with("systemd/resolv.conf") {
+        Some(ResolvedMode::Static)
+    } else {
+        None
+    }
+}
+
+/// Iterate over the text of the leading comment block, without the comment
+/// character.
+pub(crate) fn comment_lines(buf: &[u8]) -> impl Iterator<Item = &str> {
+    buf.split(|&c| c == b'\n')
+        .map_while(|line| std::str::from_utf8(line).ok())
+        .map(str::trim)
+        .take_while(|line| line.is_empty() || line.starts_with(['#', ';']))
+        .filter_map(|line| line.get(1..))
+        .map(str::trim)
+}
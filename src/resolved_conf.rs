@@ -0,0 +1,503 @@
+use std::collections::BTreeMap;
+use std::error::Error;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::net::IpAddr;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use crate::{AddrParseError, Config, ScopedIp};
+
+/// Path of the main systemd-resolved configuration file
+pub const RESOLVED_CONF: &str = "/etc/systemd/resolved.conf";
+
+/// Drop-in directories of systemd-resolved, from the highest to the lowest
+/// priority
+pub const RESOLVED_CONF_DROPIN_DIRS: &[&str] = &[
+    "/etc/systemd/resolved.conf.d",
+    "/run/systemd/resolved.conf.d",
+    "/usr/local/lib/systemd/resolved.conf.d",
+    "/usr/lib/systemd/resolved.conf.d",
+];
+
+/// The `[Resolve]` section of `resolved.conf`, as described in
+/// `man 5 resolved.conf`.
+///
+/// Settings which are not present in any file are `None` (or empty for
+/// lists), in which case systemd-resolved uses its compiled-in defaults.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{ResolvedConf, Dnssec};
+/// # fn main() {
+/// let conf = ResolvedConf::parse("
+/// [Resolve]
+/// DNS=10.0.0.1 [2001:db8::1]:53%eth0#dns.example.com
+/// Domains=example.com ~corp.example.com
+/// DNSSEC=allow-downgrade
+/// ").unwrap();
+///
+/// assert_eq!(conf.dns[1].port, Some(53));
+/// assert_eq!(conf.dnssec, Some(Dnssec::AllowDowngrade));
+///
+/// let config = conf.to_config();
+/// assert_eq!(config.get_search(), Some(&vec!["example.com".to_string()]));
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ResolvedConf {
+    /// Servers from `DNS=`
+    pub dns: Vec<ResolvedServer>,
+    /// Servers from `FallbackDNS=`. `Some(vec![])` means the fallback has
+    /// been disabled by an empty assignment.
+    pub fallback_dns: Option<Vec<ResolvedServer>>,
+    /// Search and routing domains from `Domains=`
+    pub domains: Vec<ResolvedDomain>,
+    /// Value of `DNSSEC=`
+    pub dnssec: Option<Dnssec>,
+    /// Value of `DNSOverTLS=`
+    pub dns_over_tls: Option<DnsOverTls>,
+    /// Value of `LLMNR=`
+    pub llmnr: Option<ResolveSupport>,
+    /// Value of `MulticastDNS=`
+    pub multicast_dns: Option<ResolveSupport>,
+    /// Value of `DNSStubListener=`
+    pub dns_stub_listener: Option<StubListener>,
+    /// Other settings of the `[Resolve]` section, last assignment wins
+    pub other: BTreeMap<String, String>,
+    /// Files that have been read, in the order they have been applied
+    pub sources: Vec<PathBuf>,
+}
+
+impl ResolvedConf {
+    /// Parse a single `resolved.conf` file.
+    pub fn parse(text: &str) -> Result<Self, ResolvedConfError> {
+        let mut conf = Self::default();
+        conf.apply(text)?;
+        Ok(conf)
+    }
+
+    /// Load `/etc/systemd/resolved.conf` and its drop-ins from the default
+    /// directories.
+    pub fn load() -> Result<Self, ResolvedConfError> {
+        let dirs = RESOLVED_CONF_DROPIN_DIRS
+            .iter()
+            .map(PathBuf::from)
+            .collect::<Vec<_>>();
+        Self::load_from(Path::new(RESOLVED_CONF), &dirs)
+    }
+
+    /// Load the main file and the `*.conf` drop-ins found in `dropin_dirs`.
+    ///
+    /// Like systemd, drop-ins are applied in the lexical order of their file
+    /// names regardless of the directory they are in, and a drop-in masks
+    /// the drop-ins with the same name in the directories listed after it.
+    /// The main file and the directories may be missing.
+    pub fn load_from<P: AsRef<Path>>(
+        main: &Path,
+        dropin_dirs: &[P],
+    ) -> Result<Self, ResolvedConfError> {
+        let mut files = vec![main.to_owned()];
+        let mut dropins = BTreeMap::new();
+        for dir in dropin_dirs {
+            let dir = dir.as_ref();
+            let entries = match fs::read_dir(dir) {
+                Ok(entries) => entries,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(ResolvedConfError::Io(dir.to_owned(), e)),
+            };
+            for entry in entries {
+                let path = entry
+                    .map_err(|e| ResolvedConfError::Io(dir.to_owned(), e))?
+                    .path();
+                if path.extension().map_or(true, |ext| ext != "conf") {
+                    continue;
+                }
+                if let Some(name) = path.file_name() {
+                    dropins.entry(name.to_owned()).or_insert(path);
+                }
+            }
+        }
+        files.extend(dropins.into_values());
+
+        let mut conf = Self::default();
+        for path in files {
+            let text = match fs::read_to_string(&path) {
+                Ok(text) => text,
+                Err(e) if e.kind() == io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(ResolvedConfError::Io(path, e)),
+            };
+            conf.apply(&text)
+                .map_err(|e| ResolvedConfError::InFile(path.clone(), Box::new(e)))?;
+            conf.sources.push(path);
+        }
+        Ok(conf)
+    }
+
+    /// Apply the assignments of one more file on top of this configuration.
+    pub fn apply(&mut self, text: &str) -> Result<(), ResolvedConfError> {
+        use ResolvedConfError::*;
+        let mut in_resolve = false;
+        let mut lines = text.lines().enumerate();
+        while let Some((lineno, line)) = lines.next() {
+            let mut line = line.trim().to_owned();
+            // Join continuation lines
+            while line.ends_with('\\') {
+                line.pop();
+                match lines.next() {
+                    Some((_, next)) => {
+                        line.push(' ');
+                        line.push_str(next.trim());
+                    }
+                    None => break,
+                }
+            }
+
+            if line.is_empty() || line.starts_with(['#', ';']) {
+                continue;
+            }
+            if line.starts_with('[') {
+                match line.strip_suffix(']') {
+                    Some(section) => in_resolve = section == "[Resolve",
+                    None => return Err(InvalidLine(lineno)),
+                }
+                continue;
+            }
+
+            let (key, value) = match line.split_once('=') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(InvalidLine(lineno)),
+            };
+            if !in_resolve {
+                continue;
+            }
+
+            match key {
+                "DNS" => match value.is_empty() {
+                    true => self.dns.clear(),
+                    false => self.dns.extend(parse_servers(lineno, value)?),
+                },
+                "FallbackDNS" => {
+                    let fallback = self.fallback_dns.get_or_insert_with(Vec::new);
+                    match value.is_empty() {
+                        true => fallback.clear(),
+                        false => fallback.extend(parse_servers(lineno, value)?),
+                    }
+                }
+                "Domains" => match value.is_empty() {
+                    true => self.domains.clear(),
+                    false => self
+                        .domains
+                        .extend(value.split_whitespace().map(ResolvedDomain::from_word)),
+                },
+                "DNSSEC" => self.dnssec = parse_value(lineno, value)?,
+                "DNSOverTLS" => self.dns_over_tls = parse_value(lineno, value)?,
+                "LLMNR" => self.llmnr = parse_value(lineno, value)?,
+                "MulticastDNS" => self.multicast_dns = parse_value(lineno, value)?,
+                "DNSStubListener" => self.dns_stub_listener = parse_value(lineno, value)?,
+                _ => {
+                    self.other.insert(key.to_owned(), value.to_owned());
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Map the configuration onto a `Config`.
+    ///
+    /// Nameservers come from `DNS=`, or from `FallbackDNS=` when no `DNS=`
+    /// server is configured, and lose their port and server name. Search
+    /// domains are the `Domains=` entries that are not routing-only.
+    pub fn to_config(&self) -> Config {
+        let mut config = Config::new();
+        let servers = match (&self.fallback_dns, self.dns.is_empty()) {
+            (Some(fallback), true) => fallback,
+            _ => &self.dns,
+        };
+        config.nameservers = servers.iter().map(ResolvedServer::to_scoped_ip).collect();
+
+        let search = self
+            .domains
+            .iter()
+            .filter(|domain| !domain.route_only)
+            .map(|domain| domain.name.clone())
+            .collect::<Vec<_>>();
+        if !search.is_empty() {
+            config.set_search(search);
+        }
+        config
+    }
+}
+
+fn parse_servers(lineno: usize, value: &str) -> Result<Vec<ResolvedServer>, ResolvedConfError> {
+    value
+        .split_whitespace()
+        .map(|word| {
+            ResolvedServer::from_str(word).map_err(|e| ResolvedConfError::InvalidServer(lineno, e))
+        })
+        .collect()
+}
+
+fn parse_value<T: FromStr>(lineno: usize, value: &str) -> Result<Option<T>, ResolvedConfError> {
+    match value.is_empty() {
+        true => Ok(None),
+        false => T::from_str(value)
+            .map(Some)
+            .map_err(|_| ResolvedConfError::InvalidValue(lineno)),
+    }
+}
+
+/// Parse a boolean the way systemd does
+pub(crate) fn parse_boolean(value: &str) -> Option<bool> {
+    match value {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// A DNS server as written in `DNS=` and `FallbackDNS=`, that is
+/// `address[:port][%interface][#server name]`.
+///
+/// IPv6 addresses must be enclosed in square brackets when a port is given.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedServer {
+    /// Address of the server
+    pub ip: IpAddr,
+    /// Port of the server, if not the default one
+    pub port: Option<u16>,
+    /// Name or index of the interface the server is reachable through
+    pub interface: Option<String>,
+    /// Server name used for DNS-over-TLS certificate validation and SNI
+    pub server_name: Option<String>,
+}
+
+impl ResolvedServer {
+    /// Convert the server into a `ScopedIp`. The interface becomes the scope
+    /// of IPv6 addresses and is dropped for IPv4 addresses.
+    pub fn to_scoped_ip(&self) -> ScopedIp {
+        match self.ip {
+            IpAddr::V4(ip) => ScopedIp::V4(ip),
+            IpAddr::V6(ip) => ScopedIp::V6(ip, self.interface.clone()),
+        }
+    }
+}
+
+impl FromStr for ResolvedServer {
+    type Err = AddrParseError;
+
+    fn from_str(s: &str) -> Result<Self, AddrParseError> {
+        let (s, server_name) = match s.split_once('#') {
+            Some((s, name)) if !name.is_empty() => (s, Some(name.to_owned())),
+            Some(_) => return Err(AddrParseError),
+            None => (s, None),
+        };
+        let (s, interface) = match s.split_once('%') {
+            Some((s, interface)) if !interface.is_empty() => (s, Some(interface.to_owned())),
+            Some(_) => return Err(AddrParseError),
+            None => (s, None),
+        };
+
+        let (ip, port) = if let Some(s) = s.strip_prefix('[') {
+            let (ip, port) = s.split_once(']').ok_or(AddrParseError)?;
+            let port = match port {
+                "" => None,
+                port => Some(port.strip_prefix(':').ok_or(AddrParseError)?),
+            };
+            (IpAddr::V6(ip.parse()?), port)
+        } else {
+            match s.split_once(':') {
+                Some((ip, port)) if !port.contains(':') => (IpAddr::V4(ip.parse()?), Some(port)),
+                _ => (IpAddr::from_str(s)?, None),
+            }
+        };
+        let port = match port {
+            Some(port) => Some(u16::from_str(port).map_err(|_| AddrParseError)?),
+            None => None,
+        };
+
+        Ok(Self {
+            ip,
+            port,
+            interface,
+            server_name,
+        })
+    }
+}
+
+impl fmt::Display for ResolvedServer {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match (self.ip, self.port) {
+            (IpAddr::V6(ip), Some(port)) => write!(fmt, "[{ip}]:{port}")?,
+            (ip, Some(port)) => write!(fmt, "{ip}:{port}")?,
+            (ip, None) => write!(fmt, "{ip}")?,
+        }
+        if let Some(interface) = &self.interface {
+            write!(fmt, "%{interface}")?;
+        }
+        if let Some(name) = &self.server_name {
+            write!(fmt, "#{name}")?;
+        }
+        Ok(())
+    }
+}
+
+/// A domain from `Domains=`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ResolvedDomain {
+    /// The domain, without the `~` prefix
+    pub name: String,
+    /// Whether the domain was prefixed with `~`, that is it is only used to
+    /// route queries and not as a search domain. `~.` routes all queries
+    /// that have no better match.
+    pub route_only: bool,
+}
+
+impl ResolvedDomain {
+    pub(crate) fn from_word(word: &str) -> Self {
+        match word.strip_prefix('~') {
+            Some(name) => Self {
+                name: name.to_owned(),
+                route_only: true,
+            },
+            None => Self {
+                name: word.to_owned(),
+                route_only: false,
+            },
+        }
+    }
+}
+
+impl fmt::Display for ResolvedDomain {
+    fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+        match self.route_only {
+            true => write!(fmt, "~{}", self.name),
+            false => fmt.write_str(&self.name),
+        }
+    }
+}
+
+macro_rules! setting {
+    ($(#[$doc:meta])* $name:ident { $($(#[$vdoc:meta])* $variant:ident = $value:literal,)* }) => {
+        $(#[$doc])*
+        #[derive(Copy, Clone, Debug, PartialEq, Eq)]
+        pub enum $name {
+            $($(#[$vdoc])* $variant,)*
+        }
+
+        impl FromStr for $name {
+            type Err = ();
+
+            fn from_str(s: &str) -> Result<Self, ()> {
+                match (parse_boolean(s), s) {
+                    $((_, $value) => Ok(Self::$variant),)*
+                    (Some(true), _) => Ok(Self::Yes),
+                    (Some(false), _) => Ok(Self::No),
+                    (None, _) => Err(()),
+                }
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, fmt: &mut fmt::Formatter) -> fmt::Result {
+                fmt.write_str(match self {
+                    $(Self::$variant => $value,)*
+                })
+            }
+        }
+    };
+}
+
+setting! {
+    /// Value of `DNSSEC=`
+    Dnssec {
+        /// Validate all responses
+        Yes = "yes",
+        /// Do not validate responses
+        No = "no",
+        /// Validate responses unless the server does not support DNSSEC
+        AllowDowngrade = "allow-downgrade",
+    }
+}
+
+setting! {
+    /// Value of `DNSOverTLS=`
+    DnsOverTls {
+        /// Always use DNS-over-TLS
+        Yes = "yes",
+        /// Never use DNS-over-TLS
+        No = "no",
+        /// Try DNS-over-TLS and fall back to plain DNS
+        Opportunistic = "opportunistic",
+    }
+}
+
+setting! {
+    /// Value of `LLMNR=` and `MulticastDNS=`
+    ResolveSupport {
+        /// Resolve names and respond to queries
+        Yes = "yes",
+        /// Disable the protocol
+        No = "no",
+        /// Only resolve names
+        Resolve = "resolve",
+    }
+}
+
+setting! {
+    /// Value of `DNSStubListener=`
+    StubListener {
+        /// Listen on UDP and TCP
+        Yes = "yes",
+        /// Do not listen at all
+        No = "no",
+        /// Only listen on UDP
+        Udp = "udp",
+        /// Only listen on TCP
+        Tcp = "tcp",
+    }
+}
+
+/// Error while loading `resolved.conf` or the per-link state files
+#[derive(Debug)]
+pub enum ResolvedConfError {
+    /// A file or directory could not be read
+    Io(PathBuf, io::Error),
+    /// The error occurred in the given file
+    InFile(PathBuf, Box<Self>),
+    /// A line is neither a section header nor an assignment
+    InvalidLine(usize),
+    /// A setting has an invalid value
+    InvalidValue(usize),
+    /// A server in `DNS=` or `FallbackDNS=` is invalid
+    InvalidServer(usize, AddrParseError),
+}
+
+impl fmt::Display for ResolvedConfError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Io(path, err) => write!(f, "failed to read {}: {err}", path.display()),
+            Self::InFile(path, err) => write!(f, "{}: {err}", path.display()),
+            Self::InvalidLine(line) => write!(f, "line {line} is not an assignment"),
+            Self::InvalidValue(line) => write!(f, "setting at line {line} has an invalid value"),
+            Self::InvalidServer(line, err) => {
+                write!(
+                    f,
+                    "setting at line {line} contains an invalid server: {err}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for ResolvedConfError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::Io(_, err) => Some(err),
+            Self::InFile(_, err) => Some(err),
+            Self::InvalidServer(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
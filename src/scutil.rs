@@ -0,0 +1,257 @@
+use std::error::Error;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::{AddrParseError, Config, ScopedIp};
+
+/// The resolver configuration of a macOS host, as printed by `scutil --dns`.
+///
+/// On macOS `/etc/resolv.conf` only mirrors the primary resolver; the system
+/// actually routes queries through the list of resolvers maintained by
+/// `configd`. This type holds both the default (unscoped) resolvers and the
+/// resolvers used for interface-scoped queries.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::ScutilDns;
+/// # fn main() {
+/// let output = "DNS configuration
+///
+/// resolver #1
+///   search domain[0] : example.com
+///   nameserver[0] : 192.168.1.1
+///   if_index : 6 (en0)
+///   flags    : Request A records
+///   reach    : 0x00000002 (Reachable)
+/// ";
+/// let dns = ScutilDns::parse(output).unwrap();
+/// assert_eq!(dns.resolvers.len(), 1);
+/// assert_eq!(dns.resolvers[0].if_name.as_deref(), Some("en0"));
+///
+/// let config = dns.resolvers[0].to_config();
+/// assert_eq!(config.nameservers, vec!["192.168.1.1".parse().unwrap()]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScutilDns {
+    /// Resolvers listed under "DNS configuration"
+    pub resolvers: Vec<ScutilResolver>,
+    /// Resolvers listed under "DNS configuration (for scoped queries)"
+    pub scoped_resolvers: Vec<ScutilResolver>,
+}
+
+/// A single `resolver #N` block of `scutil --dns` output.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ScutilResolver {
+    /// The number of the block (the `N` in `resolver #N`)
+    pub number: u32,
+    /// Nameservers, in the order of their `nameserver[i]` index
+    pub nameservers: Vec<ScopedIp>,
+    /// Search domains, in the order of their `search domain[i]` index
+    pub search: Vec<String>,
+    /// The domain this resolver answers for. Resolvers without a domain are
+    /// used for every query.
+    pub domain: Option<String>,
+    /// Port the nameservers listen on, if not the default one
+    pub port: Option<u16>,
+    /// Resolver options, e.g. `mdns`
+    pub options: Vec<String>,
+    /// Query timeout in seconds
+    pub timeout: Option<u32>,
+    /// Textual resolver flags, e.g. `Scoped` or `Request A records`
+    pub flags: Vec<String>,
+    /// Raw reachability flags
+    pub reach: Option<u32>,
+    /// Textual description of the reachability flags
+    pub reach_description: Vec<String>,
+    /// Search order of the resolver; lower values are tried first
+    pub order: Option<u32>,
+    /// Index of the interface the resolver is bound to
+    pub if_index: Option<u32>,
+    /// Name of the interface the resolver is bound to
+    pub if_name: Option<String>,
+    /// Keys this parser does not know about, with their raw values
+    pub other: Vec<(String, String)>,
+}
+
+impl ScutilDns {
+    /// Parse the output of `scutil --dns`.
+    pub fn parse(text: &str) -> Result<Self, ScutilParseError> {
+        use ScutilParseError::*;
+        let mut dns = Self::default();
+        let mut scoped = false;
+        let mut current: Option<ScutilResolver> = None;
+
+        for (lineno, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line == "No DNS configuration available" {
+                continue;
+            }
+
+            if line.starts_with("DNS configuration") {
+                dns.push(current.take(), scoped);
+                scoped = line.contains("scoped");
+                continue;
+            }
+
+            if let Some(number) = line.strip_prefix("resolver #") {
+                dns.push(current.take(), scoped);
+                let number = u32::from_str(number.trim()).map_err(|_| InvalidValue(lineno))?;
+                current = Some(ScutilResolver {
+                    number,
+                    ..ScutilResolver::default()
+                });
+                continue;
+            }
+
+            let resolver = match current.as_mut() {
+                Some(resolver) => resolver,
+                None => return Err(UnexpectedLine(lineno)),
+            };
+
+            let (key, value) = match line.split_once(':') {
+                Some((key, value)) => (key.trim(), value.trim()),
+                None => return Err(UnexpectedLine(lineno)),
+            };
+
+            match strip_index(key) {
+                ("nameserver", Some(_)) => {
+                    let ip = ScopedIp::from_str(value).map_err(|e| InvalidIp(lineno, e))?;
+                    resolver.nameservers.push(ip);
+                }
+                ("search domain", Some(_)) => resolver.search.push(value.to_owned()),
+                ("domain", None) => resolver.domain = Some(value.to_owned()),
+                ("port", None) => {
+                    resolver.port = Some(u16::from_str(value).map_err(|_| InvalidValue(lineno))?)
+                }
+                ("options", None) => resolver.options = split_list(value, ' '),
+                ("timeout", None) => {
+                    resolver.timeout = Some(u32::from_str(value).map_err(|_| InvalidValue(lineno))?)
+                }
+                ("flags", None) => resolver.flags = split_list(value, ','),
+                ("reach", None) => {
+                    let (raw, description) = split_description(value);
+                    let raw = raw.trim_start_matches("0x");
+                    let reach = u32::from_str_radix(raw, 16).map_err(|_| InvalidValue(lineno))?;
+                    resolver.reach = Some(reach);
+                    resolver.reach_description = split_list(description.unwrap_or(""), ',');
+                }
+                ("order", None) => {
+                    resolver.order = Some(u32::from_str(value).map_err(|_| InvalidValue(lineno))?)
+                }
+                ("if_index", None) => {
+                    let (index, name) = split_description(value);
+                    resolver.if_index =
+                        Some(u32::from_str(index).map_err(|_| InvalidValue(lineno))?);
+                    resolver.if_name = name.map(|name| name.to_owned());
+                }
+                _ => resolver.other.push((key.to_owned(), value.to_owned())),
+            }
+        }
+
+        dns.push(current, scoped);
+        Ok(dns)
+    }
+
+    fn push(&mut self, resolver: Option<ScutilResolver>, scoped: bool) {
+        if let Some(resolver) = resolver {
+            match scoped {
+                true => self.scoped_resolvers.push(resolver),
+                false => self.resolvers.push(resolver),
+            }
+        }
+    }
+}
+
+impl ScutilResolver {
+    /// Whether this resolver is only consulted for its [`domain`](#structfield.domain).
+    pub fn is_per_domain(&self) -> bool {
+        self.domain.is_some()
+    }
+
+    /// Whether the resolver carries the `Scoped` flag.
+    pub fn is_scoped(&self) -> bool {
+        self.flags.iter().any(|flag| flag == "Scoped")
+    }
+
+    /// Convert the resolver into a `Config`.
+    ///
+    /// The `domain` is stored the way `/etc/resolver/*` files store it, that
+    /// is as the domain the resolver answers for. Fields which have no
+    /// counterpart in `Config` (port, flags, reachability and so on) are
+    /// dropped.
+    pub fn to_config(&self) -> Config {
+        let mut config = Config::new();
+        config.nameservers = self.nameservers.clone();
+        if let Some(domain) = &self.domain {
+            config.set_domain(domain.clone());
+        }
+        if !self.search.is_empty() {
+            config.set_search(self.search.clone());
+        }
+        if let Some(timeout) = self.timeout {
+            config.timeout = timeout;
+        }
+        config
+    }
+}
+
+/// Split `nameserver[0]` into `("nameserver", Some(0))`
+fn strip_index(key: &str) -> (&str, Option<usize>) {
+    if let Some(key) = key.strip_suffix(']') {
+        if let Some((name, index)) = key.split_once('[') {
+            if let Ok(index) = usize::from_str(index) {
+                return (name.trim(), Some(index));
+            }
+        }
+    }
+    (key, None)
+}
+
+/// Split `6 (en0)` into `("6", Some("en0"))`
+fn split_description(value: &str) -> (&str, Option<&str>) {
+    match value.split_once('(') {
+        Some((value, rest)) => (value.trim(), Some(rest.trim_end_matches(')').trim())),
+        None => (value, None),
+    }
+}
+
+fn split_list(value: &str, separator: char) -> Vec<String> {
+    value
+        .split(separator)
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(str::to_owned)
+        .collect()
+}
+
+/// Error while parsing `scutil --dns` output
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ScutilParseError {
+    /// A line that is neither a section header, a resolver header nor a
+    /// `key : value` pair inside a resolver block.
+    UnexpectedLine(usize),
+    /// A numeric value could not be parsed.
+    InvalidValue(usize),
+    /// A nameserver could not be parsed as an IP address.
+    InvalidIp(usize, AddrParseError),
+}
+
+impl fmt::Display for ScutilParseError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::UnexpectedLine(line) => write!(f, "unexpected content at line {line}"),
+            Self::InvalidValue(line) => write!(f, "invalid value at line {line}"),
+            Self::InvalidIp(line, err) => write!(f, "invalid nameserver at line {line}: {err}"),
+        }
+    }
+}
+
+impl Error for ScutilParseError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            Self::InvalidIp(_, err) => Some(err),
+            _ => None,
+        }
+    }
+}
@@ -0,0 +1,252 @@
+use std::fmt;
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr};
+use std::str::FromStr;
+
+use serde::de::{self, Deserialize, Deserializer, IgnoredAny, MapAccess, SeqAccess};
+use serde::ser::{Serialize, SerializeStruct, Serializer};
+
+use crate::writer::write_cidr;
+use crate::{
+    AddrParseError, Config, Family, LastSearch, Lookup, Network, ResolverOption, ScopedIp,
+};
+
+/// Implement `Serialize` and `Deserialize` for `Config` as a struct of the
+/// given fields, followed by `options` as the words of `Config::options`.
+/// A missing field has its default value, and an unknown one is ignored.
+macro_rules! config_fields {
+    ($($field:ident),* $(,)?) => {
+        const FIELDS: &[&str] = &[$(stringify!($field),)* "options"];
+
+        impl Serialize for Config {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                // every field is either serialized or derived from the others
+                let Self {
+                    $($field: _,)*
+                    options: _,
+                    source_map: _,
+                } = self;
+                let mut state = serializer.serialize_struct("Config", FIELDS.len())?;
+                $(state.serialize_field(stringify!($field), &self.$field)?;)*
+                let options = self
+                    .options()
+                    .iter()
+                    .map(ToString::to_string)
+                    .collect::<Vec<_>>();
+                state.serialize_field("options", &options)?;
+                state.end()
+            }
+        }
+
+        impl<'de> de::Visitor<'de> for ConfigVisitor {
+            type Value = Config;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a resolver configuration")
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Config, A::Error> {
+                let mut config = Config::new();
+                let mut options = Vec::new();
+                while let Some(key) = map.next_key::<String>()? {
+                    match key.as_str() {
+                        $(stringify!($field) => config.$field = map.next_value()?,)*
+                        "options" => options = map.next_value()?,
+                        _ => {
+                            map.next_value::<IgnoredAny>()?;
+                        }
+                    }
+                }
+                with_options(config, &options)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Config, A::Error> {
+                let mut config = Config::new();
+                $(
+                    match seq.next_element()? {
+                        Some(value) => config.$field = value,
+                        None => return Ok(config),
+                    }
+                )*
+                let options = seq.next_element::<Vec<String>>()?.unwrap_or_default();
+                with_options(config, &options)
+            }
+        }
+    };
+}
+
+config_fields!(
+    nameservers,
+    last_search,
+    domain,
+    search,
+    sortlist,
+    debug,
+    ndots,
+    timeout,
+    attempts,
+    rotate,
+    no_check_names,
+    inet6,
+    ip6_bytestring,
+    ip6_dotint,
+    edns0,
+    single_request,
+    single_request_reopen,
+    no_tld_query,
+    use_vc,
+    no_reload,
+    trust_ad,
+    lookup,
+    family,
+    no_aaaa,
+    unknown_options,
+    unknown_directives,
+    extensions,
+);
+
+struct ConfigVisitor;
+
+impl<'de> Deserialize<'de> for Config {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_struct("Config", FIELDS, ConfigVisitor)
+    }
+}
+
+/// Rebuild the option history of `config` from the words of `options`. The
+/// fields win: the options they disagree with are dropped.
+fn with_options<E: de::Error>(mut config: Config, options: &[String]) -> Result<Config, E> {
+    for option in options {
+        let option = option
+            .parse::<ResolverOption>()
+            .map_err(|err| E::custom(format!("{err}: {option:?}")))?;
+        config.options.push(option);
+    }
+    config.options = config.applied_options().cloned().collect();
+    Ok(config)
+}
+
+/// Serialize a `LastSearch` as the directive written last, or `none`
+impl Serialize for LastSearch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Self::None => "none",
+            Self::Domain => "domain",
+            Self::Search => "search",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for LastSearch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "none" => Ok(Self::None),
+            "domain" => Ok(Self::Domain),
+            "search" => Ok(Self::Search),
+            other => Err(de::Error::unknown_variant(
+                other,
+                &["none", "domain", "search"],
+            )),
+        }
+    }
+}
+
+/// Serialize a `Family` as the word of the `family` directive
+impl Serialize for Family {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Self::Inet4 => "inet4",
+            Self::Inet6 => "inet6",
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Family {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match String::deserialize(deserializer)?.as_str() {
+            "inet4" => Ok(Self::Inet4),
+            "inet6" => Ok(Self::Inet6),
+            other => Err(de::Error::unknown_variant(other, &["inet4", "inet6"])),
+        }
+    }
+}
+
+/// Serialize a `ScopedIp` as a string, with its `%scope` if any
+impl Serialize for ScopedIp {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for ScopedIp {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        from_str(deserializer)
+    }
+}
+
+/// Serialize a `Network` as `address/prefix`, or as `address/mask` if the
+/// mask is not a prefix
+impl Serialize for Network {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut text = String::new();
+        // writing to a `String` does not fail
+        let _ = write_cidr(&mut text, self);
+        serializer.serialize_str(&text)
+    }
+}
+
+impl<'de> Deserialize<'de> for Network {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let text = String::deserialize(deserializer)?;
+        parse_cidr(&text).map_err(|err| de::Error::custom(format!("{err}: {text:?}")))
+    }
+}
+
+/// Serialize a `Lookup` as the word of the `lookup` directive
+impl Serialize for Lookup {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(match self {
+            Self::File => "file",
+            Self::Bind => "bind",
+            Self::Extra(extra) => extra,
+        })
+    }
+}
+
+impl<'de> Deserialize<'de> for Lookup {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        Ok(Self::from_word(&String::deserialize(deserializer)?))
+    }
+}
+
+fn from_str<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+where
+    D: Deserializer<'de>,
+    T: FromStr,
+    T::Err: fmt::Display,
+{
+    let text = String::deserialize(deserializer)?;
+    text.parse()
+        .map_err(|err| de::Error::custom(format!("{err}: {text:?}")))
+}
+
+/// Parse `address/prefix`, or a network as written in a `sortlist`
+fn parse_cidr(text: &str) -> Result<Network, AddrParseError> {
+    let (address, prefix) = match text.split_once('/') {
+        Some((address, prefix)) => match prefix.parse::<u32>() {
+            Ok(prefix) => (address, prefix),
+            Err(_) => return Network::from_str(text),
+        },
+        None => return Network::from_str(text),
+    };
+    match IpAddr::from_str(address)? {
+        IpAddr::V4(address) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - prefix).unwrap_or(0);
+            Ok(Network::V4(address, Ipv4Addr::from(mask)))
+        }
+        IpAddr::V6(address) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - prefix).unwrap_or(0);
+            Ok(Network::V6(address, Ipv6Addr::from(mask)))
+        }
+        _ => Err(AddrParseError),
+    }
+}
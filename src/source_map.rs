@@ -0,0 +1,203 @@
+use std::collections::BTreeMap;
+use std::mem;
+use std::path::{Path, PathBuf};
+
+use crate::grammar::{self, ConfigVisitor, Visitor};
+use crate::{Config, Family, Network, ParseError, ResolverOption, ScopedIp};
+
+/// Where a value was found in the parsed buffer.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct Span {
+    /// The line number, starting at 0 like in [`ParseError`]
+    pub line: usize,
+    /// The offset of the first byte of the value in the buffer
+    pub start: usize,
+    /// The offset of the byte after the value in the buffer
+    pub end: usize,
+}
+
+/// The location of every value of a [`Config`], as returned by
+/// [`Config::source_map`].
+///
+/// The lists are in the same order as the ones of the configuration. The
+/// `domain` and `search` directives replace each other: the one on the
+/// later line is the one [`Config::get_last_search_or_domain`] returns.
+///
+/// The map describes the parsed buffer. It is not updated when the
+/// configuration is modified afterwards, except by
+/// [`Config::glibc_normalize`].
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SourceMap {
+    /// The file the buffer was read from, if known
+    pub file: Option<PathBuf>,
+    /// Each nameserver
+    pub nameservers: Vec<Span>,
+    /// The domain of the last `domain` directive
+    pub domain: Option<Span>,
+    /// Each domain of the last `search` directive
+    pub search: Vec<Span>,
+    /// Each network of the last `sortlist` directive
+    pub sortlist: Vec<Span>,
+    /// The last occurrence of each option, by option name
+    pub options: BTreeMap<String, Span>,
+    /// Each database of the `lookup` directives
+    pub lookup: Vec<Span>,
+    /// Each protocol family of the `family` directives
+    pub family: Vec<Span>,
+    /// Each option this crate does not know, with its value
+    pub unknown_options: Vec<Span>,
+    /// Each directive this crate does not know, from its keyword to its
+    /// last argument
+    pub unknown_directives: Vec<Span>,
+    /// Values replaced by a later line, or dropped by
+    /// [`Config::glibc_normalize`]
+    pub overridden: Vec<Span>,
+}
+
+/// Fills a [`Config`] like [`ConfigVisitor`], and records where each value
+/// was found
+struct SourceMapVisitor<'a, 'c> {
+    bytes: &'a [u8],
+    config: ConfigVisitor<'c>,
+    map: SourceMap,
+}
+
+impl<'a> SourceMapVisitor<'a, '_> {
+    /// The span of `text`, a slice of the visited buffer
+    fn span(&self, lineno: usize, text: &str) -> Span {
+        let start = text.as_ptr() as usize - self.bytes.as_ptr() as usize;
+        Span {
+            line: lineno,
+            start,
+            end: start + text.len(),
+        }
+    }
+}
+
+impl<'a> Visitor<'a> for SourceMapVisitor<'a, '_> {
+    fn nameserver(&mut self, lineno: usize, ip: ScopedIp, text: &'a str) {
+        self.map.nameservers.push(self.span(lineno, text));
+        self.config.nameserver(lineno, ip, text);
+    }
+
+    fn domain(&mut self, lineno: usize, domain: &'a str) {
+        let span = self.span(lineno, domain);
+        self.map.overridden.extend(self.map.domain.replace(span));
+        self.config.domain(lineno, domain);
+    }
+
+    fn search(&mut self, lineno: usize, domains: &'a str) {
+        let search = domains
+            .split_whitespace()
+            .map(|domain| self.span(lineno, domain))
+            .collect();
+        let search = mem::replace(&mut self.map.search, search);
+        self.map.overridden.extend(search);
+        self.config.search(lineno, domains);
+    }
+
+    fn sortlist(&mut self, lineno: usize, networks: &'a str) {
+        let sortlist = mem::take(&mut self.map.sortlist);
+        self.map.overridden.extend(sortlist);
+        self.config.sortlist(lineno, networks);
+    }
+
+    fn network(&mut self, lineno: usize, network: Network, text: &'a str) {
+        self.map.sortlist.push(self.span(lineno, text));
+        self.config.network(lineno, network, text);
+    }
+
+    fn option(&mut self, lineno: usize, option: ResolverOption, text: &'a str) {
+        let span = self.span(lineno, text);
+        let name = option.name().to_owned();
+        self.map
+            .overridden
+            .extend(self.map.options.insert(name, span));
+        self.config.option(lineno, option, text);
+    }
+
+    fn lookup(&mut self, lineno: usize, database: &'a str) {
+        self.map.lookup.push(self.span(lineno, database));
+        self.config.lookup(lineno, database);
+    }
+
+    fn family(&mut self, lineno: usize, family: Family, text: &'a str) {
+        self.map.family.push(self.span(lineno, text));
+        self.config.family(lineno, family, text);
+    }
+
+    fn unknown_directive(&mut self, lineno: usize, keyword: &'a str, args: &'a str) -> bool {
+        let handled = self.config.unknown_directive(lineno, keyword, args);
+        if !handled {
+            let mut span = self.span(lineno, keyword);
+            if let Some(last) = args.split_whitespace().last() {
+                span.end = self.span(lineno, last).end;
+            }
+            self.map.unknown_directives.push(span);
+        }
+        handled
+    }
+
+    fn unknown_option(&mut self, lineno: usize, name: &'a str, value: Option<&'a str>) -> bool {
+        let handled = self.config.unknown_option(lineno, name, value);
+        if !handled {
+            let mut span = self.span(lineno, name);
+            if let Some(value) = value {
+                span.end = self.span(lineno, value).end;
+            }
+            self.map.unknown_options.push(span);
+            self.map
+                .overridden
+                .extend(self.map.options.insert(name.to_owned(), span));
+        }
+        handled
+    }
+
+    fn error(&mut self, error: ParseError) {
+        self.config.error(error);
+    }
+}
+
+impl Config {
+    /// Parse a buffer like [`parse_with_errors`](#method.parse_with_errors),
+    /// and record where each value was found, in the file `file` if given.
+    ///
+    /// ```rust
+    /// # extern crate resolv_conf;
+    /// use resolv_conf::Config;
+    /// use std::path::Path;
+    /// # fn main() {
+    /// let buf = b"options ndots:2\nsearch default.svc.cluster.local\noptions ndots:5\n";
+    /// let (config, _) = Config::parse_with_source_map(buf, Some(Path::new("/etc/resolv.conf")));
+    /// let map = config.source_map().unwrap();
+    ///
+    /// // why does this pod use ndots:5?
+    /// let span = map.options["ndots"];
+    /// assert_eq!(span.line, 2);
+    /// assert_eq!(&buf[span.start..span.end], b"ndots:5");
+    /// assert_eq!(map.overridden[0].line, 0);
+    /// # }
+    /// ```
+    pub fn parse_with_source_map(bytes: &[u8], file: Option<&Path>) -> (Self, Vec<ParseError>) {
+        let mut config = Self::new();
+        let mut errors = Vec::new();
+        let mut visitor = SourceMapVisitor {
+            bytes,
+            config: ConfigVisitor::new(&mut config, &mut errors),
+            map: SourceMap {
+                file: file.map(Path::to_owned),
+                ..SourceMap::default()
+            },
+        };
+        grammar::visit(bytes, &mut visitor);
+        let map = visitor.map;
+        config.source_map = Some(map);
+        (config, errors)
+    }
+
+    /// Return where each value was found, if the configuration was parsed
+    /// by [`parse_with_source_map`](#method.parse_with_source_map).
+    pub fn source_map(&self) -> Option<&SourceMap> {
+        self.source_map.as_ref()
+    }
+}
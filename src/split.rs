@@ -0,0 +1,187 @@
+use std::collections::BTreeMap;
+
+use crate::{Config, LinkDnsTable, ScopedIp, ScutilDns};
+
+/// A split-DNS routing table: nameservers by domain suffix, plus a default
+/// route for names no suffix matches.
+///
+/// A flat list of nameservers can not express that `corp.example.com`
+/// should be resolved by the VPN servers while everything else goes to the
+/// public ones. This table can, and is matched the way systemd-resolved and
+/// macOS do: the longest matching suffix wins.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{Config, SplitDnsTable};
+/// # fn main() {
+/// let public = Config::parse("nameserver 1.1.1.1").unwrap();
+/// let corp = Config::parse("nameserver 10.1.1.1").unwrap();
+///
+/// let mut table = SplitDnsTable::new();
+/// table.add_config(&public, &[] as &[&str]);
+/// table.add_config(&corp, &["corp.example.com"]);
+///
+/// assert_eq!(table.route("git.corp.example.com"), &["10.1.1.1".parse().unwrap()]);
+/// assert_eq!(table.route("example.com"), &["1.1.1.1".parse().unwrap()]);
+/// # }
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SplitDnsTable {
+    /// Nameservers by lowercase domain without the trailing dot
+    routes: BTreeMap<String, Vec<ScopedIp>>,
+    default: Vec<ScopedIp>,
+}
+
+impl SplitDnsTable {
+    /// Create an empty table.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Build a table from configurations tagged with the domains they serve.
+    /// Configurations without domains provide the default route.
+    pub fn from_configs<'a, I, S>(configs: I) -> Self
+    where
+        I: IntoIterator<Item = (&'a Config, &'a [S])>,
+        S: AsRef<str> + 'a,
+    {
+        let mut table = Self::new();
+        for (config, domains) in configs {
+            table.add_config(config, domains);
+        }
+        table
+    }
+
+    /// Route the given domains to the nameservers of `config`. If `domains`
+    /// is empty, the nameservers are added to the default route instead.
+    pub fn add_config<S: AsRef<str>>(&mut self, config: &Config, domains: &[S]) {
+        if domains.is_empty() {
+            self.add_default(&config.nameservers);
+        }
+        for domain in domains {
+            self.add_route(domain.as_ref(), &config.nameservers);
+        }
+    }
+
+    /// Route `domain` and its subdomains to `nameservers`, after the
+    /// nameservers already routed there. The root domain `.` is the default
+    /// route.
+    pub fn add_route(&mut self, domain: &str, nameservers: &[ScopedIp]) {
+        let domain = normalize(domain);
+        let servers = match domain.is_empty() {
+            true => &mut self.default,
+            false => self.routes.entry(domain).or_default(),
+        };
+        extend_unique(servers, nameservers);
+    }
+
+    /// Add nameservers to the default route.
+    pub fn add_default(&mut self, nameservers: &[ScopedIp]) {
+        extend_unique(&mut self.default, nameservers);
+    }
+
+    /// Return the nameservers of the default route.
+    pub fn default_route(&self) -> &[ScopedIp] {
+        &self.default
+    }
+
+    /// Iterate over the domains of the table and their nameservers, not
+    /// including the default route.
+    pub fn routes(&self) -> impl Iterator<Item = (&str, &[ScopedIp])> {
+        self.routes
+            .iter()
+            .map(|(domain, servers)| (domain.as_str(), servers.as_slice()))
+    }
+
+    /// Return the nameservers a query for `name` should be sent to.
+    pub fn route(&self, name: &str) -> &[ScopedIp] {
+        match self.matching_domain(name) {
+            Some(domain) => &self.routes[domain],
+            None => &self.default,
+        }
+    }
+
+    /// Return the longest domain of the table `name` falls under, or `None`
+    /// if the default route applies.
+    pub fn matching_domain(&self, name: &str) -> Option<&str> {
+        let name = normalize(name);
+        let mut suffix = name.as_str();
+        loop {
+            if let Some((domain, _)) = self.routes.get_key_value(suffix) {
+                return Some(domain);
+            }
+            match suffix.split_once('.') {
+                Some((_, parent)) => suffix = parent,
+                None => return None,
+            }
+        }
+    }
+}
+
+impl From<&ScutilDns> for SplitDnsTable {
+    /// Resolvers with a domain become routes; the others, typically only the
+    /// first one, provide the default route. Resolvers are added by
+    /// ascending search order, and scoped resolvers are ignored.
+    fn from(dns: &ScutilDns) -> Self {
+        let mut resolvers = dns.resolvers.iter().collect::<Vec<_>>();
+        resolvers.sort_by_key(|resolver| resolver.order.unwrap_or(u32::MAX));
+
+        let mut table = Self::new();
+        for resolver in resolvers {
+            match &resolver.domain {
+                Some(domain) => table.add_route(domain, &resolver.nameservers),
+                None => table.add_default(&resolver.nameservers),
+            }
+        }
+        table
+    }
+}
+
+impl From<&LinkDnsTable> for SplitDnsTable {
+    /// Every search and routing domain of a link is routed to the servers of
+    /// the link, and the links that are default routes provide the default
+    /// route.
+    fn from(links: &LinkDnsTable) -> Self {
+        let mut table = Self::new();
+        for link in links.links.values() {
+            for domain in &link.domains {
+                table.add_route(&domain.name, &link.servers);
+            }
+            if link.is_default_route() {
+                table.add_default(&link.servers);
+            }
+        }
+        table
+    }
+}
+
+fn normalize(domain: &str) -> String {
+    domain.trim_end_matches('.').to_ascii_lowercase()
+}
+
+fn extend_unique(servers: &mut Vec<ScopedIp>, new: &[ScopedIp]) {
+    for server in new {
+        if !servers.contains(server) {
+            servers.push(server.clone());
+        }
+    }
+}
+
+/// Return the number of labels of `suffix` if `name` is equal to it or a
+/// subdomain of it. The root domain matches every name with zero labels.
+pub(crate) fn suffix_labels(name: &str, suffix: &str) -> Option<usize> {
+    let name = name.trim_end_matches('.');
+    let suffix = suffix.trim_end_matches('.');
+    if suffix.is_empty() {
+        return Some(0);
+    }
+    if name.len() < suffix.len() {
+        return None;
+    }
+    let (head, tail) = name.as_bytes().split_at(name.len() - suffix.len());
+    match tail.eq_ignore_ascii_case(suffix.as_bytes()) && (head.is_empty() || head.ends_with(b"."))
+    {
+        true => Some(suffix.split('.').count()),
+        false => None,
+    }
+}
@@ -0,0 +1,451 @@
+use std::ffi::{CString, OsString};
+use std::fs::{self, File};
+use std::io::{self, Read};
+use std::os::raw::c_int;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::io::{AsRawFd, FromRawFd};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use libc::{
+    IN_ATTRIB, IN_CLOSE_WRITE, IN_CREATE, IN_DELETE, IN_DELETE_SELF, IN_IGNORED, IN_MODIFY,
+    IN_MOVED_FROM, IN_MOVED_TO, IN_MOVE_SELF, IN_ONLYDIR, IN_Q_OVERFLOW,
+};
+
+use crate::Config;
+
+const DIR_MASK: u32 = IN_MODIFY
+    | IN_ATTRIB
+    | IN_CLOSE_WRITE
+    | IN_MOVED_FROM
+    | IN_MOVED_TO
+    | IN_CREATE
+    | IN_DELETE
+    | IN_DELETE_SELF
+    | IN_MOVE_SELF
+    | IN_ONLYDIR;
+
+/// Maximum number of symlinks followed, like `MAXSYMLINKS` on Linux
+const MAX_SYMLINKS: usize = 40;
+
+/// What waiting for inotify events ended with
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+enum Events {
+    /// No event arrived in time
+    Timeout,
+    /// Only events about other files arrived
+    Irrelevant,
+    /// An event about a file of interest arrived
+    Relevant,
+}
+
+/// Watches a resolv.conf file with inotify and yields the configuration
+/// each time it changes. **(linux-only)**
+///
+/// The directories holding the file and every target of its symlink chain
+/// (for instance `/run/systemd/resolve/stub-resolv.conf`) are watched, so
+/// files replaced with a rename, deleted and recreated, or symlinks pointed
+/// somewhere else are all noticed. Bursts of events are coalesced: the file
+/// is only read once no event arrived for the [debounce
+/// delay](#method.with_debounce).
+///
+/// The iterator blocks until the next change. It yields an error when the
+/// file is missing or can not be parsed, and a new configuration once it is
+/// fixed. It only ends after an error it can not recover from, such as the
+/// removal of the directory holding the file, which it yields first.
+///
+/// ```rust,no_run
+/// # extern crate resolv_conf;
+/// use resolv_conf::ConfigWatcher;
+/// # fn main() {
+/// for config in ConfigWatcher::new("/etc/resolv.conf").unwrap() {
+///     match config {
+///         Ok(config) => println!("new nameservers: {:?}", config.nameservers),
+///         Err(e) => println!("resolv.conf is broken: {}", e),
+///     }
+/// }
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct ConfigWatcher {
+    path: PathBuf,
+    inotify: File,
+    debounce: Duration,
+    watches: Vec<Watch>,
+    /// Whether an error stopped the watch for good
+    failed: bool,
+}
+
+/// A watched directory and the names of the files of interest in it
+#[derive(Debug)]
+struct Watch {
+    wd: c_int,
+    names: Vec<OsString>,
+}
+
+impl ConfigWatcher {
+    /// Start watching the file at `path`. The directory holding it must
+    /// exist, the file itself may not.
+    pub fn new<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        // SAFETY: inotify_init1 takes no pointer
+        let fd = unsafe { libc::inotify_init1(libc::IN_NONBLOCK | libc::IN_CLOEXEC) };
+        if fd < 0 {
+            return Err(io::Error::last_os_error());
+        }
+        let mut watcher = Self {
+            path: path.as_ref().to_owned(),
+            // SAFETY: the descriptor was just created and nothing else owns it
+            inotify: unsafe { File::from_raw_fd(fd) },
+            debounce: Duration::from_millis(100),
+            watches: Vec::new(),
+            failed: false,
+        };
+        watcher.sync_watches()?;
+        Ok(watcher)
+    }
+
+    /// Set how long the file must stay untouched before it is read (100
+    /// milliseconds by default).
+    pub fn with_debounce(mut self, debounce: Duration) -> Self {
+        self.debounce = debounce;
+        self
+    }
+
+    /// Return the path being watched.
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Return the file and the targets of its symlink chain, which are the
+    /// files whose changes are reported.
+    pub fn watched_files(&self) -> Vec<PathBuf> {
+        symlink_chain(&self.path)
+    }
+
+    /// Read the file now.
+    pub fn load(&self) -> io::Result<Config> {
+        let buf = fs::read(&self.path)?;
+        Config::parse(buf).map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e))
+    }
+
+    /// Wait for the next change for at most `timeout`, and return `None`
+    /// if there was none, or if the watch stopped after an error.
+    pub fn next_timeout(&mut self, timeout: Duration) -> Option<io::Result<Config>> {
+        self.next_change(Some(timeout))
+    }
+
+    /// Whether the watch stopped after an error it can not recover from.
+    pub fn has_failed(&self) -> bool {
+        self.failed
+    }
+
+    fn next_change(&mut self, timeout: Option<Duration>) -> Option<io::Result<Config>> {
+        if self.failed {
+            return None;
+        }
+        let deadline = timeout.map(|timeout| Instant::now() + timeout);
+        loop {
+            let remaining =
+                deadline.map(|deadline| deadline.saturating_duration_since(Instant::now()));
+            match self.wait_events(remaining) {
+                Ok(Events::Relevant) => break,
+                Ok(_) if remaining == Some(Duration::from_secs(0)) => return None,
+                Ok(_) => continue,
+                Err(e) => return Some(Err(self.fail(e))),
+            }
+        }
+
+        // Let the burst of events settle: events about other files do not
+        // end the wait, relevant ones restart it
+        let mut quiet_until = Instant::now() + self.debounce;
+        loop {
+            let remaining = quiet_until.saturating_duration_since(Instant::now());
+            if remaining == Duration::from_secs(0) {
+                break;
+            }
+            match self.wait_events(Some(remaining)) {
+                Ok(Events::Relevant) => quiet_until = Instant::now() + self.debounce,
+                Ok(Events::Irrelevant) | Ok(Events::Timeout) => continue,
+                Err(e) => return Some(Err(self.fail(e))),
+            }
+        }
+
+        // Without the directory of the file, no change can be noticed
+        if let Err(e) = self.sync_watches() {
+            return Some(Err(self.fail(e)));
+        }
+        Some(self.load())
+    }
+
+    /// Stop the watch for good because of `err`
+    fn fail(&mut self, err: io::Error) -> io::Error {
+        self.failed = true;
+        err
+    }
+
+    /// Wait for events for at most `timeout`, forever if `None`, and tell
+    /// whether one of them is about a file of interest.
+    fn wait_events(&mut self, timeout: Option<Duration>) -> io::Result<Events> {
+        let timeout = match timeout {
+            Some(timeout) => timeout.as_millis().min(c_int::MAX as u128) as c_int,
+            None => -1,
+        };
+        let mut fds = libc::pollfd {
+            fd: self.inotify.as_raw_fd(),
+            events: libc::POLLIN,
+            revents: 0,
+        };
+        // SAFETY: `fds` is a single valid pollfd, borrowed for the call
+        match unsafe { libc::poll(&mut fds, 1, timeout) } {
+            n if n < 0 => {
+                let err = io::Error::last_os_error();
+                return match err.kind() {
+                    io::ErrorKind::Interrupted => Ok(Events::Irrelevant),
+                    _ => Err(err),
+                };
+            }
+            0 => return Ok(Events::Timeout),
+            _ => {}
+        }
+
+        let mut events = Events::Irrelevant;
+        let mut buf = [0u8; 4096];
+        loop {
+            let len = match self.inotify.read(&mut buf) {
+                Ok(len) => len,
+                Err(e) if e.kind() == io::ErrorKind::WouldBlock => break,
+                Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e),
+            };
+            let mut offset = 0;
+            // struct inotify_event { int wd; uint32_t mask, cookie, len; char name[]; }
+            while offset + 16 <= len {
+                let field = |i: usize| {
+                    let start = offset + 4 * i;
+                    [buf[start], buf[start + 1], buf[start + 2], buf[start + 3]]
+                };
+                let wd = c_int::from_ne_bytes(field(0));
+                let mask = u32::from_ne_bytes(field(1));
+                let name_len = u32::from_ne_bytes(field(3)) as usize;
+                let name = &buf[offset + 16..(offset + 16 + name_len).min(len)];
+                let name = name.split(|&c| c == 0).next().unwrap_or(&[]);
+                offset += 16 + name_len;
+
+                // The watches removed by `sync_watches` are not in
+                // `self.watches` anymore, so the `IN_IGNORED` they cause
+                // is not a change
+                let watch = self.watches.iter().find(|watch| watch.wd == wd);
+                let relevant = mask & IN_Q_OVERFLOW != 0
+                    || match watch {
+                        Some(_) if mask & (IN_IGNORED | IN_DELETE_SELF | IN_MOVE_SELF) != 0 => true,
+                        Some(watch) => watch.names.iter().any(|n| n.as_bytes() == name),
+                        None => false,
+                    };
+                if relevant {
+                    events = Events::Relevant;
+                }
+            }
+        }
+        Ok(events)
+    }
+
+    /// Watch the directories of the files of the symlink chain, and only
+    /// them.
+    fn sync_watches(&mut self) -> io::Result<()> {
+        let mut wanted: Vec<(PathBuf, Vec<OsString>)> = Vec::new();
+        for file in symlink_chain(&self.path) {
+            let (dir, name) = match (file.parent(), file.file_name()) {
+                (Some(dir), Some(name)) => (dir, name),
+                _ => continue,
+            };
+            let dir = match dir.as_os_str().is_empty() {
+                true => Path::new("."),
+                false => dir,
+            };
+            match wanted.iter_mut().find(|(d, _)| d == dir) {
+                Some((_, names)) => names.push(name.to_owned()),
+                None => wanted.push((dir.to_owned(), vec![name.to_owned()])),
+            }
+        }
+
+        let fd = self.inotify.as_raw_fd();
+        let mut watches: Vec<Watch> = Vec::new();
+        for (i, (dir, names)) in wanted.into_iter().enumerate() {
+            let path = CString::new(dir.as_os_str().as_bytes())
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidInput, e))?;
+            // SAFETY: `path` is a NUL-terminated string that outlives the call
+            let wd = unsafe { libc::inotify_add_watch(fd, path.as_ptr(), DIR_MASK) };
+            if wd < 0 {
+                let err = io::Error::last_os_error();
+                // Only the directory of the file itself is mandatory
+                match i {
+                    0 => return Err(err),
+                    _ => continue,
+                }
+            }
+            // Two paths may be the same directory
+            match watches.iter_mut().find(|watch| watch.wd == wd) {
+                Some(watch) => watch.names.extend(names),
+                None => watches.push(Watch { wd, names }),
+            }
+        }
+
+        for old in &self.watches {
+            if !watches.iter().any(|watch| watch.wd == old.wd) {
+                // SAFETY: inotify_rm_watch takes no pointer
+                unsafe { libc::inotify_rm_watch(fd, old.wd) };
+            }
+        }
+        self.watches = watches;
+        Ok(())
+    }
+}
+
+impl Iterator for ConfigWatcher {
+    type Item = io::Result<Config>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.next_change(None)
+    }
+}
+
+/// Return `path` followed by the targets of its symlinks
+fn symlink_chain(path: &Path) -> Vec<PathBuf> {
+    let mut chain = vec![path.to_owned()];
+    let mut current = path.to_owned();
+    for _ in 0..MAX_SYMLINKS {
+        let target = match fs::read_link(&current) {
+            Ok(target) => target,
+            Err(_) => break,
+        };
+        current = match current.parent() {
+            Some(parent) => parent.join(target),
+            None => target,
+        };
+        if chain.contains(&current) {
+            break;
+        }
+        chain.push(current.clone());
+    }
+    chain
+}
+
+#[cfg(feature = "stream")]
+pub use self::stream::ConfigStream;
+
+#[cfg(feature = "stream")]
+mod stream {
+    use std::collections::VecDeque;
+    use std::io;
+    use std::pin::Pin;
+    use std::sync::{Arc, Mutex};
+    use std::task::{Context, Poll, Waker};
+    use std::thread;
+    use std::time::Duration;
+
+    use futures_core::Stream;
+
+    use super::ConfigWatcher;
+    use crate::Config;
+
+    /// An asynchronous stream of configurations, returned by
+    /// [`ConfigWatcher::into_stream`]. **(linux-only, requires the `stream`
+    /// feature)**
+    ///
+    /// The stream does not depend on any runtime: the watcher runs on a
+    /// dedicated thread, which exits shortly after the stream is dropped.
+    /// The stream ends like the watcher, after an error it can not recover
+    /// from. Only the last few items are kept if it is not polled.
+    #[derive(Debug)]
+    pub struct ConfigStream {
+        shared: Arc<Mutex<Shared>>,
+    }
+
+    /// Maximum number of items waiting to be polled
+    const QUEUE_LIMIT: usize = 8;
+
+    #[derive(Debug, Default)]
+    struct Shared {
+        queue: VecDeque<io::Result<Config>>,
+        waker: Option<Waker>,
+        /// The stream was dropped
+        closed: bool,
+        /// The watcher failed and will not yield anything else
+        ended: bool,
+    }
+
+    impl Shared {
+        /// Queue `item`, replacing an error of the same kind queued last
+        /// and dropping the oldest items beyond the limit: each
+        /// configuration replaces the previous ones anyway.
+        fn push(&mut self, item: io::Result<Config>) {
+            if let (Some(Err(last)), Err(err)) = (self.queue.back(), &item) {
+                if last.kind() == err.kind() {
+                    self.queue.pop_back();
+                }
+            }
+            if self.queue.len() == QUEUE_LIMIT {
+                self.queue.pop_front();
+            }
+            self.queue.push_back(item);
+        }
+    }
+
+    impl ConfigWatcher {
+        /// Turn the watcher into an asynchronous `Stream`.
+        pub fn into_stream(mut self) -> ConfigStream {
+            let shared = Arc::new(Mutex::new(Shared::default()));
+            let thread_shared = shared.clone();
+            thread::spawn(move || loop {
+                let item = self.next_timeout(Duration::from_millis(250));
+                let mut shared = match thread_shared.lock() {
+                    Ok(shared) => shared,
+                    Err(_) => return,
+                };
+                if shared.closed {
+                    return;
+                }
+                if let Some(item) = item {
+                    shared.push(item);
+                }
+                shared.ended = self.failed;
+                if shared.ended || !shared.queue.is_empty() {
+                    if let Some(waker) = shared.waker.take() {
+                        waker.wake();
+                    }
+                }
+                if shared.ended {
+                    return;
+                }
+            });
+            ConfigStream { shared }
+        }
+    }
+
+    impl Stream for ConfigStream {
+        type Item = io::Result<Config>;
+
+        fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+            let mut shared = match self.shared.lock() {
+                Ok(shared) => shared,
+                Err(_) => return Poll::Ready(None),
+            };
+            match shared.queue.pop_front() {
+                Some(item) => Poll::Ready(Some(item)),
+                None if shared.ended => Poll::Ready(None),
+                None => {
+                    shared.waker = Some(cx.waker().clone());
+                    Poll::Pending
+                }
+            }
+        }
+    }
+
+    impl Drop for ConfigStream {
+        fn drop(&mut self) {
+            if let Ok(mut shared) = self.shared.lock() {
+                shared.closed = true;
+            }
+        }
+    }
+}
@@ -0,0 +1,326 @@
+use std::fmt;
+use std::io;
+use std::net::{Ipv4Addr, Ipv6Addr};
+
+use crate::options::known_options;
+use crate::ResolverOption::{Attempts, Ndots, Timeout};
+use crate::{Config, Dialect, Family, LastSearch, Lookup, Network};
+
+/// How [`Writer`] writes the mask of a `sortlist` network.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum MaskStyle {
+    /// As an address, like `10.0.0.0/255.0.0.0`, which is what resolvers
+    /// read
+    Dotted,
+    /// As a prefix length, like `10.0.0.0/8`. A mask that is not a prefix
+    /// is still written as an address.
+    Cidr,
+}
+
+/// Write a [`Config`] in a configurable style.
+///
+/// The default writer writes the same text as `Display`. Unlike `Display`,
+/// a writer can group the options, add a header comment, and leave out what
+/// a given resolver does not read.
+///
+/// ```rust
+/// # extern crate resolv_conf;
+/// use resolv_conf::{Config, Dialect, MaskStyle, Writer};
+/// # fn main() {
+/// let config = Config::parse("\
+/// nameserver 10.0.0.1
+/// sortlist 10.0.0.0/255.0.0.0
+/// options rotate edns0 trust-ad
+/// lookup file bind
+/// ").unwrap();
+///
+/// let writer = Writer::new()
+///     .header("Generated by provisioning, do not edit")
+///     .group_options(32)
+///     .mask_style(MaskStyle::Cidr)
+///     .explicit_defaults(true)
+///     .dialect(Dialect::Glibc);
+/// assert_eq!(writer.format(&config), "\
+/// ## Generated by provisioning, do not edit
+/// nameserver 10.0.0.1
+/// sortlist 10.0.0.0/8
+/// options ndots:1 timeout:5
+/// options attempts:2 rotate edns0
+/// options trust-ad
+/// ");
+/// assert_eq!(Writer::new().format(&config), config.to_string());
+/// # }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Writer {
+    header: Option<String>,
+    group_options: Option<usize>,
+    mask_style: MaskStyle,
+    explicit_defaults: bool,
+    dialect: Option<Dialect>,
+}
+
+impl Default for Writer {
+    fn default() -> Self {
+        Self {
+            header: None,
+            group_options: None,
+            mask_style: MaskStyle::Dotted,
+            explicit_defaults: false,
+            dialect: None,
+        }
+    }
+}
+
+impl Writer {
+    /// Create a writer with the style of `Display`.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Start the file with `header`, each of its lines as a comment.
+    pub fn header<S: Into<String>>(mut self, header: S) -> Self {
+        self.header = Some(header.into());
+        self
+    }
+
+    /// Write the options on as few `options` lines as possible, starting
+    /// a new one before a line gets longer than `width` bytes. Use
+    /// `usize::MAX` to write a single line.
+    pub fn group_options(mut self, width: usize) -> Self {
+        self.group_options = Some(width);
+        self
+    }
+
+    /// Write the masks of the sortlist in the given style.
+    pub fn mask_style(mut self, style: MaskStyle) -> Self {
+        self.mask_style = style;
+        self
+    }
+
+    /// Write `ndots`, `timeout` and `attempts` even if they have their
+    /// default value and were not set explicitly.
+    pub fn explicit_defaults(mut self, explicit: bool) -> Self {
+        self.explicit_defaults = explicit;
+        self
+    }
+
+    /// Leave out the directives and options `dialect` does not read.
+    pub fn dialect(mut self, dialect: Dialect) -> Self {
+        self.dialect = Some(dialect);
+        self
+    }
+
+    /// Write `config` to `writer`.
+    pub fn write<W: io::Write>(&self, config: &Config, mut writer: W) -> io::Result<()> {
+        writer.write_all(self.format(config).as_bytes())
+    }
+
+    /// Return the text of `config`.
+    pub fn format(&self, config: &Config) -> String {
+        let mut text = String::new();
+        // writing to a `String` does not fail
+        let _ = self.fmt_config(config, &mut text);
+        text
+    }
+
+    pub(crate) fn fmt_config<W: fmt::Write>(&self, config: &Config, fmt: &mut W) -> fmt::Result {
+        let Config {
+            nameservers,
+            last_search,
+            domain,
+            search,
+            sortlist,
+            lookup,
+            family,
+            // listed by `known_options`
+            debug: _,
+            ndots: _,
+            timeout: _,
+            attempts: _,
+            rotate: _,
+            no_check_names: _,
+            inet6: _,
+            ip6_bytestring: _,
+            ip6_dotint: _,
+            edns0: _,
+            single_request: _,
+            single_request_reopen: _,
+            no_tld_query: _,
+            use_vc: _,
+            no_reload: _,
+            trust_ad: _,
+            no_aaaa: _,
+            unknown_options,
+            unknown_directives,
+            // only tells which options were set explicitly
+            options: _,
+            // only the handlers that stored them know how to write them
+            extensions: _,
+            source_map: _,
+        } = config;
+
+        if let Some(header) = &self.header {
+            for line in header.lines() {
+                match line.is_empty() {
+                    true => writeln!(fmt, "#")?,
+                    false => writeln!(fmt, "# {line}")?,
+                }
+            }
+        }
+
+        for nameserver in nameservers.iter() {
+            writeln!(fmt, "nameserver {nameserver}")?;
+        }
+
+        if last_search != &LastSearch::Domain {
+            if let Some(domain) = domain {
+                writeln!(fmt, "domain {domain}")?;
+            }
+        }
+
+        if let Some(search) = search {
+            if !search.is_empty() {
+                write!(fmt, "search")?;
+                for suffix in search.iter() {
+                    write!(fmt, " {suffix}")?;
+                }
+                writeln!(fmt)?;
+            }
+        }
+
+        if last_search == &LastSearch::Domain {
+            if let Some(domain) = domain {
+                writeln!(fmt, "domain {domain}")?;
+            }
+        }
+
+        // musl reads nothing but the nameservers, the search domains, and
+        // `ndots`, `timeout` and `attempts`
+        let musl = self.dialect == Some(Dialect::Musl);
+        if !sortlist.is_empty() && !musl {
+            write!(fmt, "sortlist")?;
+            for network in sortlist.iter() {
+                match self.mask_style {
+                    MaskStyle::Dotted => write!(fmt, " {network}")?,
+                    MaskStyle::Cidr => {
+                        write!(fmt, " ")?;
+                        write_cidr(fmt, network)?;
+                    }
+                }
+            }
+            writeln!(fmt)?;
+        }
+
+        let openbsd = matches!(self.dialect, None | Some(Dialect::OpenBsd));
+        if !lookup.is_empty() && openbsd {
+            write!(fmt, "lookup")?;
+            for db in lookup.iter() {
+                match db {
+                    Lookup::File => write!(fmt, " file")?,
+                    Lookup::Bind => write!(fmt, " bind")?,
+                    Lookup::Extra(extra) => write!(fmt, " {extra}")?,
+                }
+            }
+            writeln!(fmt)?;
+        }
+
+        if !family.is_empty() && openbsd {
+            write!(fmt, "family")?;
+            for fam in family.iter() {
+                match fam {
+                    Family::Inet4 => write!(fmt, " inet4")?,
+                    Family::Inet6 => write!(fmt, " inet6")?,
+                }
+            }
+            writeln!(fmt)?;
+        }
+
+        let mut options = Vec::new();
+        for option in known_options(config, self.explicit_defaults) {
+            if !musl || matches!(option, Ndots(_) | Timeout(_) | Attempts(_)) {
+                options.push(option.to_string());
+            }
+        }
+        if !musl {
+            for (name, value) in unknown_options {
+                match value {
+                    Some(value) => options.push(format!("{name}:{value}")),
+                    None => options.push(name.clone()),
+                }
+            }
+        }
+        self.write_options(fmt, &options)?;
+
+        if !musl {
+            for (keyword, args) in unknown_directives {
+                write!(fmt, "{keyword}")?;
+                for arg in args {
+                    write!(fmt, " {arg}")?;
+                }
+                writeln!(fmt)?;
+            }
+        }
+
+        Ok(())
+    }
+
+    fn write_options<W: fmt::Write>(&self, fmt: &mut W, options: &[String]) -> fmt::Result {
+        let width = match self.group_options {
+            Some(width) => width,
+            None => {
+                for option in options {
+                    writeln!(fmt, "options {option}")?;
+                }
+                return Ok(());
+            }
+        };
+
+        let mut line = String::new();
+        for option in options {
+            if !line.is_empty() && line.len() + 1 + option.len() > width {
+                writeln!(fmt, "{line}")?;
+                line.clear();
+            }
+            if line.is_empty() {
+                line.push_str("options");
+            }
+            line.push(' ');
+            line.push_str(option);
+        }
+        if !line.is_empty() {
+            writeln!(fmt, "{line}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Write `address/prefix`, or `address/mask` if the mask is not a prefix
+pub(crate) fn write_cidr<W: fmt::Write>(fmt: &mut W, network: &Network) -> fmt::Result {
+    match *network {
+        Network::V4(address, mask) => match prefix_v4(mask) {
+            Some(prefix) => write!(fmt, "{address}/{prefix}"),
+            None => write!(fmt, "{network}"),
+        },
+        Network::V6(address, mask) => match prefix_v6(mask) {
+            Some(prefix) => write!(fmt, "{address}/{prefix}"),
+            None => write!(fmt, "{network}"),
+        },
+    }
+}
+
+fn prefix_v4(mask: Ipv4Addr) -> Option<u32> {
+    let bits = u32::from(mask);
+    match bits.leading_ones() + bits.trailing_zeros() {
+        32 => Some(bits.leading_ones()),
+        _ => None,
+    }
+}
+
+fn prefix_v6(mask: Ipv6Addr) -> Option<u32> {
+    let bits = u128::from(mask);
+    match bits.leading_ones() + bits.trailing_zeros() {
+        128 => Some(bits.leading_ones()),
+        _ => None,
+    }
+}
@@ -0,0 +1,147 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{Config, MaskStyle, SymlinkPolicy, WriteOptions, Writer};
+use std::fs;
+use std::path::Path;
+
+fn names(dir: &Path) -> Vec<String> {
+    let mut names = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().into_string().unwrap())
+        .collect::<Vec<_>>();
+    names.sort();
+    names
+}
+
+#[test]
+fn test_write_new_and_replace() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let options = WriteOptions::new(SymlinkPolicy::Replace);
+
+    let config = Config::parse("nameserver 10.0.0.1\noptions rotate").unwrap();
+    config.write_to_path(&path, options).unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), config.to_string());
+
+    let config = Config::parse("nameserver 10.0.0.2").unwrap();
+    config.write_to_path(&path, options).unwrap();
+    assert_eq!(Config::from_path(&path).unwrap(), config);
+    assert_eq!(names(dir.path()), ["resolv.conf"]);
+
+    let config = Config::parse("sortlist 10.0.0.0/255.0.0.0").unwrap();
+    Writer::new()
+        .mask_style(MaskStyle::Cidr)
+        .write_to_path(&config, &path, options)
+        .unwrap();
+    assert_eq!(fs::read_to_string(&path).unwrap(), "sortlist 10.0.0.0/8\n");
+}
+
+#[test]
+fn test_backup() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let options = WriteOptions::new(SymlinkPolicy::Replace).backup(true);
+
+    // nothing to back up yet
+    Config::parse("nameserver 10.0.0.1")
+        .unwrap()
+        .write_to_path(&path, options)
+        .unwrap();
+    assert_eq!(names(dir.path()), ["resolv.conf"]);
+
+    Config::parse("nameserver 10.0.0.2")
+        .unwrap()
+        .write_to_path(&path, options)
+        .unwrap();
+    Config::parse("nameserver 10.0.0.3")
+        .unwrap()
+        .write_to_path(&path, options)
+        .unwrap();
+    assert_eq!(names(dir.path()), ["resolv.conf", "resolv.conf.bak"]);
+    assert_eq!(
+        fs::read_to_string(dir.path().join("resolv.conf.bak")).unwrap(),
+        "nameserver 10.0.0.2\n"
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "nameserver 10.0.0.3\n");
+}
+
+#[test]
+fn test_missing_directory() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("missing").join("resolv.conf");
+    let options = WriteOptions::new(SymlinkPolicy::Follow);
+    assert!(Config::new().write_to_path(path, options).is_err());
+    assert!(names(dir.path()).is_empty());
+}
+
+#[cfg(unix)]
+#[test]
+fn test_permissions() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let options = WriteOptions::new(SymlinkPolicy::Replace);
+
+    Config::new().write_to_path(&path, options).unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o644);
+
+    fs::set_permissions(&path, fs::Permissions::from_mode(0o640)).unwrap();
+    Config::parse("nameserver 10.0.0.1")
+        .unwrap()
+        .write_to_path(&path, options)
+        .unwrap();
+    let mode = fs::metadata(&path).unwrap().permissions().mode();
+    assert_eq!(mode & 0o777, 0o640);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_symlinks() {
+    use std::os::unix::fs::symlink;
+
+    let dir = tempfile::tempdir().unwrap();
+    fs::create_dir(dir.path().join("run")).unwrap();
+    let target = dir.path().join("run").join("stub-resolv.conf");
+    let link = dir.path().join("resolv.conf");
+    fs::write(&target, "nameserver 127.0.0.53\n").unwrap();
+    symlink("run/stub-resolv.conf", &link).unwrap();
+
+    let config = Config::parse("nameserver 10.0.0.1").unwrap();
+    config
+        .write_to_path(&link, WriteOptions::new(SymlinkPolicy::Follow))
+        .unwrap();
+    assert!(fs::symlink_metadata(&link)
+        .unwrap()
+        .file_type()
+        .is_symlink());
+    assert_eq!(
+        fs::read_to_string(&target).unwrap(),
+        "nameserver 10.0.0.1\n"
+    );
+    assert_eq!(names(&dir.path().join("run")), ["stub-resolv.conf"]);
+
+    let config = Config::parse("nameserver 10.0.0.2").unwrap();
+    config
+        .write_to_path(&link, WriteOptions::new(SymlinkPolicy::Replace))
+        .unwrap();
+    assert!(fs::symlink_metadata(&link).unwrap().file_type().is_file());
+    assert_eq!(fs::read_to_string(&link).unwrap(), "nameserver 10.0.0.2\n");
+    assert_eq!(
+        fs::read_to_string(&target).unwrap(),
+        "nameserver 10.0.0.1\n"
+    );
+
+    // a dangling symlink is written through too
+    let dangling = dir.path().join("dangling.conf");
+    symlink("run/new.conf", &dangling).unwrap();
+    config
+        .write_to_path(&dangling, WriteOptions::new(SymlinkPolicy::Follow))
+        .unwrap();
+    assert_eq!(
+        fs::read_to_string(dir.path().join("run").join("new.conf")).unwrap(),
+        "nameserver 10.0.0.2\n"
+    );
+}
@@ -0,0 +1,140 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{BlockError, Config, ManagedBlock, SymlinkPolicy, WriteOptions};
+use std::fs;
+
+const TEXT: &str = "# Generated by hand\nnameserver 10.0.0.1\n\nsearch example.com\n";
+
+#[test]
+fn test_replace() {
+    let docker = ManagedBlock::new("docker");
+    let vpn = ManagedBlock::new("vpn");
+
+    let text = docker.replace(TEXT, "nameserver 172.17.0.1").unwrap();
+    let text = vpn
+        .replace(&text, "nameserver 10.8.0.1\noptions rotate\n")
+        .unwrap();
+    assert_eq!(
+        text,
+        [
+            TEXT,
+            "# BEGIN docker\nnameserver 172.17.0.1\n# END docker\n",
+            "# BEGIN vpn\nnameserver 10.8.0.1\noptions rotate\n# END vpn\n",
+        ]
+        .concat()
+    );
+    let config = Config::parse(&text).unwrap();
+    assert_eq!(config.nameservers.len(), 3);
+    assert!(config.rotate);
+
+    // replacing a block leaves the rest alone
+    let replaced = docker.replace(&text, "nameserver 172.18.0.1\n").unwrap();
+    assert_eq!(replaced, text.replace("172.17.0.1", "172.18.0.1"));
+    assert_eq!(
+        docker.replace(&replaced, "nameserver 172.18.0.1").unwrap(),
+        replaced
+    );
+    assert_eq!(
+        docker.contents(&replaced).unwrap(),
+        Some("nameserver 172.18.0.1\n")
+    );
+    assert_eq!(docker.contents(TEXT).unwrap(), None);
+
+    let empty = docker.replace(&text, "").unwrap();
+    assert!(empty.contains("# BEGIN docker\n# END docker\n"));
+    assert_eq!(docker.contents(&empty).unwrap(), Some(""));
+
+    // a missing final newline is added before the block
+    assert_eq!(
+        vpn.replace("nameserver 10.0.0.1", "nameserver 10.8.0.1")
+            .unwrap(),
+        "nameserver 10.0.0.1\n# BEGIN vpn\nnameserver 10.8.0.1\n# END vpn\n"
+    );
+}
+
+#[test]
+fn test_remove() {
+    let block = ManagedBlock::new("vpn");
+    let text = block.replace(TEXT, "nameserver 10.8.0.1").unwrap();
+    assert_eq!(block.remove(&text).unwrap(), TEXT);
+    assert_eq!(block.remove(TEXT).unwrap(), TEXT);
+
+    // in the middle of the file, without a final newline
+    let text = "nameserver 10.0.0.1\n# BEGIN vpn\nnameserver 10.8.0.1\n# END vpn\r\nsearch a";
+    assert_eq!(block.find(text).unwrap(), Some(20..63));
+    assert_eq!(block.remove(text).unwrap(), "nameserver 10.0.0.1\nsearch a");
+    let text = "# BEGIN vpn\n# END vpn";
+    assert_eq!(block.remove(text).unwrap(), "");
+}
+
+#[test]
+fn test_errors() {
+    let block = ManagedBlock::new("vpn");
+    let err = |text: &str| block.find(text).unwrap_err();
+    assert!(matches!(
+        err("nameserver 10.0.0.1\n# BEGIN vpn\n"),
+        BlockError::Unterminated(1)
+    ));
+    assert!(matches!(
+        err("# BEGIN vpn\n# BEGIN vpn\n# END vpn\n"),
+        BlockError::Unterminated(0)
+    ));
+    assert!(matches!(err("# END vpn\n"), BlockError::UnexpectedEnd(0)));
+    assert!(matches!(
+        err("# BEGIN vpn\n# END vpn\n# BEGIN vpn\n# END vpn\n"),
+        BlockError::Duplicate(2)
+    ));
+    assert!(matches!(
+        block.replace(TEXT, "# END vpn\nnameserver 10.0.0.2"),
+        Err(BlockError::MarkerInContents)
+    ));
+
+    // only the errors in the block count
+    let text = "options foo\n";
+    let invalid = block.replace(text, "nameserver 10.0.0.2\nnameserver bad\n");
+    match invalid {
+        Err(BlockError::Invalid(errors)) => assert_eq!(errors.len(), 1),
+        other => panic!("unexpected {other:?}"),
+    }
+    assert!(block.replace(text, "nameserver 10.0.0.2").is_ok());
+
+    // other files are not validated
+    let hosts = fs::read_to_string("tests/hosts").unwrap();
+    let block = ManagedBlock::new("docker").validate(false);
+    let text = block.replace(&hosts, "172.17.0.2 web").unwrap();
+    assert!(text.starts_with(&hosts));
+    // the final newline added before the block stays
+    assert_eq!(block.remove(&text).unwrap(), hosts + "\n");
+}
+
+#[test]
+fn test_update_path() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let options = WriteOptions::new(SymlinkPolicy::Follow);
+    let block = ManagedBlock::with_markers(">>> vpn", "<<< vpn");
+
+    // nothing to remove from a missing file
+    assert!(!block.update_path(&path, None, options).unwrap());
+    assert!(!path.exists());
+
+    assert!(block
+        .update_path(&path, Some("nameserver 10.8.0.1"), options)
+        .unwrap());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        ">>> vpn\nnameserver 10.8.0.1\n<<< vpn\n"
+    );
+    assert!(!block
+        .update_path(&path, Some("nameserver 10.8.0.1"), options)
+        .unwrap());
+
+    fs::write(&path, "nameserver 10.0.0.1\n>>> vpn\n<<< vpn\n# the end\n").unwrap();
+    assert!(block.update_path(&path, None, options).unwrap());
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        "nameserver 10.0.0.1\n# the end\n"
+    );
+    assert!(!block.update_path(&path, None, options).unwrap());
+}
@@ -0,0 +1,137 @@
+extern crate resolv_conf;
+
+use resolv_conf::{
+    Config, ConfigBuilder, Dialect, Family, Lookup, Network, ResolverOption, ScopedIp,
+    ValidationError,
+};
+use std::net::{IpAddr, Ipv4Addr};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_build() {
+    let config = ConfigBuilder::new()
+        .nameserver(ip("10.0.0.1"))
+        .nameserver("::1".parse::<IpAddr>().unwrap())
+        .domain("example.com")
+        .search(vec!["a.example".to_string(), "b.example".to_string()])
+        .sortlist("10.0.0.0/255.0.0.0".parse::<Network>().unwrap())
+        .option(ResolverOption::Rotate)
+        .option(ResolverOption::Ndots(1))
+        .build()
+        .unwrap();
+    let parsed = Config::parse(
+        "nameserver 10.0.0.1\nnameserver ::1\ndomain example.com\nsearch a.example b.example\n\
+         sortlist 10.0.0.0/255.0.0.0\noptions rotate ndots:1",
+    )
+    .unwrap();
+    assert_eq!(config, parsed);
+    assert_eq!(config.explicit_ndots(), Some(1));
+    assert_eq!(config.validate(Some(Dialect::Glibc)), Ok(()));
+}
+
+#[test]
+fn test_invariants() {
+    let errors = ConfigBuilder::new()
+        .nameserver(Ipv4Addr::new(10, 0, 0, 1))
+        .nameserver(Ipv4Addr::new(10, 0, 0, 2))
+        .nameserver(Ipv4Addr::new(10, 0, 0, 3))
+        .nameserver(Ipv4Addr::new(10, 0, 0, 4))
+        .search(Vec::<String>::new())
+        .option(ResolverOption::Ndots(0))
+        .option(ResolverOption::Attempts(0))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            ValidationError::TooManyNameservers { count: 4, max: 3 },
+            ValidationError::EmptySearch,
+            ValidationError::OutOfRange {
+                option: "ndots",
+                value: 0,
+                min: 1,
+                max: u32::MAX,
+            },
+            ValidationError::OutOfRange {
+                option: "attempts",
+                value: 0,
+                min: 1,
+                max: u32::MAX,
+            },
+        ]
+    );
+    assert_eq!(errors[0].to_string(), "4 nameservers, at most 3 are used");
+
+    let errors = ConfigBuilder::new()
+        .search(["ok.example", "bad example", ""])
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            ValidationError::InvalidDomain("bad example".into()),
+            ValidationError::InvalidDomain("".into()),
+        ]
+    );
+    let long = format!("{}.example", "a".repeat(250));
+    assert!(ConfigBuilder::new().domain(long).build().is_err());
+    assert!(ConfigBuilder::new().domain("example.com.").build().is_ok());
+}
+
+#[test]
+fn test_dialects() {
+    let builder = ConfigBuilder::new()
+        .search(["a", "b", "c", "d", "e", "f", "g"])
+        .option(ResolverOption::Ndots(20))
+        .option(ResolverOption::Edns0)
+        .lookup(Lookup::File)
+        .family(Family::Inet4);
+    assert!(builder.clone().build().is_ok());
+
+    let errors = builder.clone().dialect(Dialect::Glibc).build().unwrap_err();
+    assert_eq!(
+        errors,
+        [
+            ValidationError::TooManySearchDomains { count: 7, max: 6 },
+            ValidationError::OutOfRange {
+                option: "ndots",
+                value: 20,
+                min: 1,
+                max: 15,
+            },
+            ValidationError::Unsupported("lookup".into(), Dialect::Glibc),
+            ValidationError::Unsupported("family".into(), Dialect::Glibc),
+        ]
+    );
+
+    let errors = builder.clone().dialect(Dialect::Musl).build().unwrap_err();
+    assert_eq!(errors.len(), 4);
+    assert_eq!(
+        errors[3].to_string(),
+        "option edns0 is not supported by musl"
+    );
+
+    let errors = builder.dialect(Dialect::OpenBsd).build().unwrap_err();
+    assert_eq!(errors.len(), 2);
+}
+
+#[test]
+fn test_musl_drops() {
+    // what the musl writer leaves out is not valid for musl
+    let config = Config::parse_with_errors(
+        b"nameserver 10.0.0.1\nsortlist 10.0.0.0/255.0.0.0\noptions ndots:2 foo\nport 53\n",
+    )
+    .0;
+    assert!(config.validate(None).is_ok());
+    assert_eq!(
+        config.validate(Some(Dialect::Musl)).unwrap_err(),
+        [
+            ValidationError::Unsupported("option foo".into(), Dialect::Musl),
+            ValidationError::Unsupported("sortlist".into(), Dialect::Musl),
+            ValidationError::Unsupported("port".into(), Dialect::Musl),
+        ]
+    );
+}
@@ -0,0 +1,97 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, ResolverOption, ScopedIp};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn canonical(buf: &str) -> Config {
+    let mut config = Config::parse_with_errors(buf.as_bytes()).0;
+    config.canonicalize();
+    config
+}
+
+#[test]
+fn test_nameservers() {
+    let config = canonical(
+        "nameserver 10.0.0.1\nnameserver ::ffff:10.0.0.1\nnameserver ::ffff:a00:2\nnameserver fe80::1%eth0\nnameserver fe80::1%eth0\nnameserver ::1",
+    );
+    assert_eq!(
+        config.nameservers,
+        [
+            ip("10.0.0.1"),
+            ip("10.0.0.2"),
+            ip("fe80::1%eth0"),
+            ip("::1")
+        ]
+    );
+}
+
+#[test]
+fn test_domains() {
+    let config = canonical("domain Example.COM.");
+    assert!(config.get_domain().is_none());
+    assert_eq!(config.get_search().unwrap(), &["example.com"]);
+    assert_eq!(canonical("domain .").get_search().unwrap(), &["."]);
+
+    let config = canonical("domain example.com\nsearch a.example A.Example. b.example");
+    assert!(config.get_domain().is_none());
+    assert_eq!(config.get_search().unwrap(), &["a.example", "b.example"]);
+
+    let config = canonical("search a.example\ndomain example.com");
+    assert!(config.get_domain().is_none());
+    assert_eq!(config.get_search().unwrap(), &["example.com"]);
+    assert_eq!(
+        config.get_last_search_or_domain().collect::<Vec<_>>(),
+        ["example.com"]
+    );
+}
+
+#[test]
+fn test_options() {
+    let config = canonical("options ndots:3 rotate foo:1\noptions ndots:2 rotate foo:2 bar");
+    assert_eq!(config.ndots, 2);
+    assert_eq!(
+        config.options(),
+        [
+            ResolverOption::Ndots(2),
+            ResolverOption::Rotate,
+            ResolverOption::Unknown("foo".into(), Some("2".into())),
+            ResolverOption::Unknown("bar".into(), None),
+        ]
+    );
+    assert_eq!(
+        config.unknown_options,
+        [
+            ("foo".to_string(), Some("2".to_string())),
+            ("bar".to_string(), None)
+        ]
+    );
+    // explicit defaults are kept
+    assert_eq!(canonical("options ndots:1").explicit_ndots(), Some(1));
+}
+
+#[test]
+fn test_semantically_eq() {
+    let a = Config::parse("nameserver 10.0.0.1\nsearch example.com\nsortlist 10.0.0.0/255.0.0.0")
+        .unwrap();
+    let b = Config::parse(
+        "nameserver ::ffff:10.0.0.1\ndomain other.example\nsearch EXAMPLE.COM. example.com\n\
+         sortlist 10.0.0.0/255.0.0.0 10.0.0.0/255.0.0.0",
+    )
+    .unwrap();
+    assert_ne!(a, b);
+    assert!(a.semantically_eq(&b));
+    assert!(b.semantically_eq(&a));
+
+    // a lone domain is searched like a search list of that domain
+    let c = Config::parse("nameserver 10.0.0.1\ndomain example.com\nsortlist 10.0.0.0/255.0.0.0")
+        .unwrap();
+    assert!(a.semantically_eq(&c));
+    assert!(c.semantically_eq(&a));
+
+    // these are not resolved the same way
+    let d = Config::parse("nameserver 10.0.0.2\nnameserver 10.0.0.1\nsearch example.com").unwrap();
+    assert!(!a.semantically_eq(&d));
+}
@@ -0,0 +1,88 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, ConfigRef, Family, Network, ScopedIp};
+use std::fs;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_same_as_config() {
+    let mut files = vec![
+        "tests/resolv.conf-simple",
+        "tests/resolv.conf-linux",
+        "tests/resolv.conf-macos",
+        "tests/resolv.conf-openbsd",
+    ]
+    .into_iter()
+    .map(|path| fs::read(path).unwrap())
+    .collect::<Vec<_>>();
+    files.push(b"search a.example\ndomain b.example\n".to_vec());
+    files.push(b"domain b.example\nsearch a.example c.example\nsearch\n".to_vec());
+    files.push(
+        b"nameserver 10.0.0.1 extra\nnameserver foo\noptions ndots:x rotate\n\xff\n".to_vec(),
+    );
+    files.push(b"sortlist 10.0.0.0/255.0.0.0\nsortlist 130.155.0.0 bad\n".to_vec());
+    files.push(b"options strict-error ndots:x a:b\nport 5353 udp\nretrans\n".to_vec());
+
+    for buf in files {
+        let (expected, expected_errors) = Config::parse_with_errors(&buf);
+        let (config, errors) = ConfigRef::parse_with_errors(&buf);
+        assert_eq!(config.to_owned(), expected);
+        assert_eq!(format!("{:?}", errors), format!("{:?}", expected_errors));
+        assert_eq!(
+            config.last_search_or_domain().collect::<Vec<_>>(),
+            expected.get_last_search_or_domain().collect::<Vec<_>>()
+        );
+    }
+}
+
+#[test]
+fn test_borrowed_accessors() {
+    let buf = "
+domain example.com
+search example.com sub.example.com
+nameserver 10.0.0.1
+nameserver fe80::1%1
+sortlist 130.155.160.0/255.255.240.0
+lookup file bind yp
+family inet6 inet4
+options ndots:3 rotate edns0
+";
+    let config = ConfigRef::parse(buf).unwrap();
+    assert_eq!(config.domain(), Some("example.com"));
+    assert_eq!(
+        config.search().unwrap().collect::<Vec<_>>(),
+        ["example.com", "sub.example.com"]
+    );
+    assert_eq!(
+        config.last_search_or_domain().collect::<Vec<_>>(),
+        ["example.com", "sub.example.com"]
+    );
+    assert_eq!(
+        config.nameservers().collect::<Vec<_>>(),
+        [ip("10.0.0.1"), ip("fe80::1%1")]
+    );
+    assert_eq!(
+        config.sortlist().collect::<Vec<_>>(),
+        [Network::V4(
+            "130.155.160.0".parse().unwrap(),
+            "255.255.240.0".parse().unwrap()
+        )]
+    );
+    assert_eq!(config.lookup().collect::<Vec<_>>(), ["file", "bind", "yp"]);
+    assert_eq!(
+        config.family().collect::<Vec<_>>(),
+        [Family::Inet6, Family::Inet4]
+    );
+    assert_eq!(config.ndots, 3);
+    assert!(config.rotate && config.edns0);
+    assert_eq!(config.timeout, 5);
+}
+
+#[test]
+fn test_parse_errors() {
+    assert!(ConfigRef::parse("options ndots:1 foo:1").is_err());
+    assert!(ConfigRef::parse("invalid foo.com").is_err());
+}
@@ -0,0 +1,170 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, ConfigChange, ResolverOption, ScopedIp};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn parse(s: &str) -> Config {
+    Config::parse(s).unwrap()
+}
+
+fn patch(old: &Config, new: &Config) -> Config {
+    let mut patched = old.clone();
+    old.diff(new).apply(&mut patched);
+    patched
+}
+
+#[test]
+fn test_nameservers() {
+    let old = parse("nameserver 10.0.0.1\nnameserver 10.0.0.2\nnameserver 10.0.0.3");
+    let new = parse("nameserver 10.0.0.3\nnameserver 10.0.0.4\nnameserver 10.0.0.1");
+    assert_eq!(
+        old.diff(&new).changes,
+        [
+            ConfigChange::NameserverRemoved(ip("10.0.0.2")),
+            ConfigChange::NameserversReordered(vec![ip("10.0.0.3"), ip("10.0.0.1")]),
+            ConfigChange::NameserverAdded(1, ip("10.0.0.4")),
+        ]
+    );
+    assert_eq!(patch(&old, &new), new);
+    assert_eq!(patch(&new, &old), old);
+    assert_eq!(
+        old.diff(&new).to_string(),
+        "nameserver 10.0.0.2 removed\nnameservers reordered: 10.0.0.3 10.0.0.1\nnameserver 10.0.0.4 added\n"
+    );
+}
+
+#[test]
+fn test_same_config() {
+    // `Display` writes these differently, but a resolver sees the same
+    let old = parse("domain example.com\nsearch example.com\noptions rotate edns0");
+    let new = parse("search example.com\ndomain example.com\noptions edns0 rotate");
+    assert_ne!(old.to_string(), new.to_string());
+    assert!(old.diff(&new).is_empty());
+    assert_eq!(old.diff(&new).to_string(), "");
+}
+
+#[test]
+fn test_search() {
+    let old = parse("domain example.com");
+    let new = parse("domain example.com\nsearch a.example b.example");
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.changes,
+        [ConfigChange::SearchChanged {
+            old: vec!["example.com".into()],
+            new: vec!["a.example".into(), "b.example".into()],
+        }]
+    );
+    assert_eq!(
+        diff.to_string(),
+        "search list changed from [example.com] to [a.example b.example]\n"
+    );
+    assert_eq!(patch(&old, &new), new);
+    assert_eq!(patch(&new, &old), old);
+
+    let new = parse("domain corp.example.com");
+    assert_eq!(
+        old.diff(&new).to_string(),
+        "domain example.com changed to corp.example.com\nsearch list changed from [example.com] to [corp.example.com]\n"
+    );
+    assert_eq!(patch(&old, &new), new);
+    assert_eq!(patch(&new, &Config::new()), Config::new());
+}
+
+#[test]
+fn test_options() {
+    let old = Config::parse_with_errors(b"options ndots:2 rotate no-ip6-dotint foo:1").0;
+    let new = Config::parse_with_errors(b"options ip6-dotint timeout:3 foo:2").0;
+    let diff = old.diff(&new);
+    assert!(diff.changes.contains(&ConfigChange::OptionChanged {
+        old: Some(ResolverOption::Ndots(2)),
+        new: None,
+    }));
+    assert!(diff.changes.contains(&ConfigChange::OptionChanged {
+        old: Some(ResolverOption::NoIp6Dotint),
+        new: Some(ResolverOption::Ip6Dotint),
+    }));
+    assert!(diff
+        .to_string()
+        .contains("option no-ip6-dotint changed to ip6-dotint\n"));
+    assert!(diff.changes.contains(&ConfigChange::OptionChanged {
+        old: Some(ResolverOption::Unknown("foo".into(), Some("1".into()))),
+        new: Some(ResolverOption::Unknown("foo".into(), Some("2".into()))),
+    }));
+    assert!(diff.to_string().contains("option foo:1 changed to foo:2\n"));
+    assert!(diff.to_string().contains("option timeout:3 added\n"));
+
+    let patched = patch(&old, &new);
+    assert_eq!(patched, new);
+    assert_eq!(patched.explicit_ndots(), None);
+    assert_eq!(patched.explicit_ip6_dotint(), Some(true));
+    assert_eq!(patch(&new, &old), old);
+}
+
+#[test]
+fn test_explicit_defaults() {
+    // equal configurations have an empty diff
+    let old = parse("options ndots:1 timeout:5 attempts:2 no-ip6-dotint");
+    assert_eq!(old, Config::new());
+    assert!(old.diff(&Config::new()).is_empty());
+    assert!(Config::new().diff(&old).is_empty());
+
+    let new = parse("options ndots:3");
+    assert_eq!(
+        old.diff(&new).changes,
+        [ConfigChange::OptionChanged {
+            old: Some(ResolverOption::Ndots(1)),
+            new: Some(ResolverOption::Ndots(3)),
+        }]
+    );
+    assert_eq!(patch(&old, &new), new);
+}
+
+#[test]
+fn test_other_directives() {
+    let old = parse("sortlist 10.0.0.0/255.0.0.0\nlookup file bind\nfamily inet4");
+    let new = Config::parse_with_errors(b"lookup bind\nfamily inet6 inet4\nport 53").0;
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.to_string(),
+        "sortlist changed from [10.0.0.0/255.0.0.0] to []
+lookup changed from [file bind] to [bind]
+family changed from [inet4] to [inet6 inet4]
+unknown directives changed from [] to [port]
+"
+    );
+    assert_eq!(patch(&old, &new), new);
+}
+
+#[test]
+fn test_extensions() {
+    let mut old = parse("nameserver 10.0.0.1");
+    old.extensions.insert("port".into(), "53".into());
+    old.extensions.insert("retrans".into(), "3".into());
+    let mut new = old.clone();
+    new.extensions.insert("port".into(), "5353".into());
+    new.extensions.remove("retrans");
+    new.extensions.insert("zone".into(), "a".into());
+
+    let diff = old.diff(&new);
+    assert_eq!(
+        diff.changes[0],
+        ConfigChange::ExtensionChanged {
+            name: "port".into(),
+            old: Some("53".into()),
+            new: Some("5353".into()),
+        }
+    );
+    assert_eq!(
+        diff.to_string(),
+        "extension port changed from 53 to 5353
+extension retrans=3 removed
+extension zone=a added
+"
+    );
+    assert_eq!(patch(&old, &new), new);
+    assert_eq!(patch(&new, &old), old);
+}
@@ -427,3 +427,41 @@ fn test_display_preservers_last_search() {
 
     assert_eq!(original_config, restored_config);
 }
+
+#[test]
+fn test_unknown_tokens_are_kept() {
+    let (mut config, errors) = resolv_conf::Config::parse_with_errors(
+        b"nameserver 10.0.0.1\noptions rotate strict-error res_nquery_retries:4\nport 5353 udp\nretrans\n",
+    );
+    assert_eq!(errors.len(), 4);
+    assert_eq!(
+        config.unknown_options,
+        vec![
+            ("strict-error".to_owned(), None),
+            ("res_nquery_retries".to_owned(), Some("4".to_owned())),
+        ]
+    );
+    assert_eq!(
+        config.unknown_directives,
+        vec![
+            ("port".to_owned(), vec!["5353".to_owned(), "udp".to_owned()]),
+            ("retrans".to_owned(), vec![]),
+        ]
+    );
+
+    config.ndots = 3;
+    let output = config.to_string();
+    assert_eq!(
+        output,
+        "nameserver 10.0.0.1
+options ndots:3
+options rotate
+options strict-error
+options res_nquery_retries:4
+port 5353 udp
+retrans
+"
+    );
+    let (restored, _) = resolv_conf::Config::parse_with_errors(output.as_bytes());
+    assert_eq!(restored, config);
+}
@@ -0,0 +1,89 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{Config, Fallback, LoadError, ScopedIp};
+use std::fs;
+use std::path::Path;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_from_reader() {
+    let config = Config::from_reader(&b"nameserver 10.0.0.1\noptions rotate\n"[..]).unwrap();
+    assert_eq!(config.nameservers, vec![ip("10.0.0.1")]);
+    assert!(config.rotate);
+
+    match Config::from_reader(&b"nameserver foo\n"[..]) {
+        Err(LoadError::Parse(None, _)) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_from_path() {
+    let config = Config::from_path("tests/resolv.conf-simple").unwrap();
+    assert_eq!(
+        config,
+        Config::parse(fs::read("tests/resolv.conf-simple").unwrap()).unwrap()
+    );
+
+    let dir = tempfile::tempdir().unwrap();
+    let missing = dir.path().join("resolv.conf");
+    let err = Config::from_path(&missing).unwrap_err();
+    assert!(matches!(err, LoadError::NotFound(_)));
+    assert_eq!(err.path(), Some(missing.as_path()));
+
+    // reading a directory fails after it is opened
+    match Config::from_path(dir.path()) {
+        Err(LoadError::Io(Some(path), _)) => assert_eq!(path, dir.path()),
+        other => panic!("unexpected result: {:?}", other),
+    }
+
+    let invalid = dir.path().join("invalid");
+    fs::write(&invalid, "nameserver 10.0.0.1\ninvalid foo.com\n").unwrap();
+    let err = Config::from_path(&invalid).unwrap_err();
+    assert!(matches!(err, LoadError::Parse(Some(_), _)));
+    assert_eq!(
+        err.to_string(),
+        format!(
+            "failed to parse {}: directive at line 1 is not recognized",
+            invalid.display()
+        )
+    );
+}
+
+#[test]
+fn test_from_system_path_fallback() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let local = vec![ip("127.0.0.1"), ip("::1")];
+
+    let system = Config::from_system_path(&path).unwrap();
+    assert_eq!(system.path, path);
+    assert_eq!(system.fallback, Some(Fallback::MissingFile));
+    assert_eq!(system.config.nameservers, local);
+    assert_eq!(system.config.ndots, 1);
+
+    fs::write(&path, "").unwrap();
+    let system = Config::from_system_path(&path).unwrap();
+    assert_eq!(system.fallback, Some(Fallback::NoNameservers));
+    assert_eq!(system.config.nameservers, local);
+
+    fs::write(&path, "options ndots:3\n").unwrap();
+    let system = Config::from_system_path(&path).unwrap();
+    assert_eq!(system.fallback, Some(Fallback::NoNameservers));
+    assert_eq!(system.config.ndots, 3);
+
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let system = Config::from_system_path(&path).unwrap();
+    assert_eq!(system.fallback, None);
+    assert_eq!(system.config.nameservers, vec![ip("10.0.0.1")]);
+}
+
+#[test]
+fn test_from_system_path_errors() {
+    let err = Config::from_system_path(Path::new("tests")).unwrap_err();
+    assert!(matches!(err, LoadError::Io(..)));
+}
@@ -0,0 +1,97 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{ManagedBy, Ownership, OwnershipEvidence};
+use std::fs;
+
+fn detect_file(path: &str) -> Ownership {
+    Ownership::detect_path(path).unwrap()
+}
+
+#[test]
+fn test_detect_fixtures() {
+    assert_eq!(
+        detect_file("tests/resolv.conf-openbsd"),
+        Ownership {
+            managed_by: ManagedBy::Dhcp,
+            evidence: vec![(
+                ManagedBy::Dhcp,
+                OwnershipEvidence::Header("Generated by em0 dhclient".into())
+            )],
+        }
+    );
+    assert_eq!(
+        detect_file("tests/resolv.conf-macos").managed_by,
+        ManagedBy::MacOs
+    );
+    assert_eq!(
+        detect_file("tests/resolv.conf-systemd-stub").managed_by,
+        ManagedBy::SystemdResolved
+    );
+    assert_eq!(
+        detect_file("tests/resolvconf/etc/resolv.conf.d/head").managed_by,
+        ManagedBy::Resolvconf
+    );
+
+    let simple = detect_file("tests/resolv.conf-simple");
+    assert_eq!(simple.managed_by, ManagedBy::Unmanaged);
+    assert!(!simple.is_managed());
+    // only the leading comments count
+    assert!(!detect_file("tests/resolv.conf-linux").is_managed());
+}
+
+#[test]
+fn test_detect_headers() {
+    let detect = |text: &str| Ownership::detect(text.as_bytes()).managed_by;
+    assert_eq!(
+        detect("# Generated by NetworkManager\nsearch home\nnameserver 192.168.1.1\n"),
+        ManagedBy::NetworkManager
+    );
+    assert_eq!(
+        detect(
+            "# This file was automatically generated by WSL. To stop automatic generation of \
+             this file, add the following entry to /etc/wsl.conf:\n\
+             # [network]\n# generateResolvConf = false\nnameserver 172.22.48.1\n"
+        ),
+        ManagedBy::Wsl
+    );
+    assert_eq!(
+        detect("# Generated by dhcpcd from eth0.dhcp\nnameserver 10.0.0.1\n"),
+        ManagedBy::Dhcp
+    );
+    assert_eq!(
+        detect("nameserver 10.0.0.1\n# Generated by NetworkManager\n"),
+        ManagedBy::Unmanaged
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_detect_symlink() {
+    let dir = tempfile::tempdir().unwrap();
+    let target = dir.path().join("run/NetworkManager/resolv.conf");
+    fs::create_dir_all(target.parent().unwrap()).unwrap();
+    fs::write(
+        &target,
+        "# Generated by NetworkManager\nnameserver 10.0.0.1\n",
+    )
+    .unwrap();
+    let link = dir.path().join("resolv.conf");
+    std::os::unix::fs::symlink(&target, &link).unwrap();
+
+    let ownership = Ownership::detect_path(&link).unwrap();
+    assert_eq!(ownership.managed_by, ManagedBy::NetworkManager);
+    assert_eq!(
+        ownership.evidence,
+        vec![
+            (
+                ManagedBy::NetworkManager,
+                OwnershipEvidence::Symlink(target)
+            ),
+            (
+                ManagedBy::NetworkManager,
+                OwnershipEvidence::Header("Generated by NetworkManager".into())
+            ),
+        ]
+    );
+}
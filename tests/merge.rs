@@ -0,0 +1,190 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, Family, Lookup, MergePolicy, MergeStrategy, ScopedIp};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn parse(s: &str) -> Config {
+    Config::parse(s).unwrap()
+}
+
+#[test]
+fn test_list_strategies() {
+    let base = parse("nameserver 10.0.0.1\nnameserver 10.0.0.2\nsortlist 10.0.0.0/255.0.0.0");
+    let overlay = parse("nameserver 10.0.0.2\nnameserver 10.0.0.3");
+
+    let replace = base.merge(&overlay, MergePolicy::default());
+    assert_eq!(replace.nameservers, [ip("10.0.0.2"), ip("10.0.0.3")]);
+    // an empty list does not replace anything
+    assert_eq!(replace.sortlist, base.sortlist);
+
+    let policy = MergePolicy {
+        nameservers: MergeStrategy::Append,
+        ..MergePolicy::default()
+    };
+    assert_eq!(
+        base.merge(&overlay, policy).nameservers,
+        [ip("10.0.0.1"), ip("10.0.0.2"), ip("10.0.0.3")]
+    );
+
+    let policy = MergePolicy {
+        nameservers: MergeStrategy::Prepend,
+        ..MergePolicy::default()
+    };
+    assert_eq!(
+        base.merge(&overlay, policy).nameservers,
+        [ip("10.0.0.2"), ip("10.0.0.3"), ip("10.0.0.1")]
+    );
+}
+
+#[test]
+fn test_domain_and_search() {
+    let base = parse("search a.example b.example");
+    let overlay = parse("domain c.example");
+
+    // the `domain` of the overlay comes last, so it wins
+    let merged = base.merge(&overlay, MergePolicy::default());
+    assert_eq!(merged.get_domain().unwrap(), "c.example");
+    assert_eq!(
+        merged.get_last_search_or_domain().collect::<Vec<_>>(),
+        ["c.example"]
+    );
+    assert_eq!(parse(&merged.to_string()), merged);
+
+    let policy = MergePolicy {
+        search: MergeStrategy::Append,
+        ..MergePolicy::default()
+    };
+    let merged = base.merge(&overlay, policy);
+    assert_eq!(
+        merged.get_last_search_or_domain().collect::<Vec<_>>(),
+        ["a.example", "b.example", "c.example"]
+    );
+
+    // an overlay without `domain` or `search` keeps the search list
+    let merged = overlay.merge(&parse("nameserver 10.0.0.1"), MergePolicy::default());
+    assert_eq!(
+        merged.get_last_search_or_domain().collect::<Vec<_>>(),
+        ["c.example"]
+    );
+}
+
+#[test]
+fn test_options_last_wins() {
+    // unknown options are errors, but they are kept
+    let base = Config::parse_with_errors(b"options ndots:3 timeout:1 rotate strict-error foo:1").0;
+    let overlay = Config::parse_with_errors(b"options ndots:1 use-vc foo:2").0;
+    let merged = base.merge(&overlay, MergePolicy::default());
+    assert_eq!(merged.ndots, 1);
+    assert_eq!(merged.explicit_ndots(), Some(1));
+    assert_eq!(merged.timeout, 1);
+    assert!(merged.rotate);
+    assert!(merged.use_vc);
+    assert_eq!(
+        merged.unknown_options,
+        [
+            ("strict-error".to_string(), None),
+            ("foo".to_string(), Some("2".to_string())),
+        ]
+    );
+    assert!(merged.to_string().contains("options ndots:1\n"));
+    let options = merged
+        .options()
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        options,
+        [
+            "ndots:3",
+            "timeout:1",
+            "rotate",
+            "strict-error",
+            "ndots:1",
+            "use-vc",
+            "foo:2"
+        ]
+    );
+
+    // options set through the fields count too
+    let mut overlay = Config::new();
+    overlay.attempts = 4;
+    overlay.edns0 = true;
+    let merged = base.merge(&overlay, MergePolicy::default());
+    assert_eq!((merged.attempts, merged.ndots), (4, 3));
+    assert!(merged.edns0);
+}
+
+#[test]
+fn test_provenance() {
+    let distro = parse("nameserver 10.0.0.1\nsearch example.com\noptions ndots:2 timeout:3");
+    let dhcp = parse("nameserver 192.168.1.1\nsearch home\noptions timeout:1");
+    let vpn = parse("nameserver 10.8.0.1\ndomain corp.example.com\noptions rotate");
+    let policy = MergePolicy {
+        nameservers: MergeStrategy::Prepend,
+        search: MergeStrategy::Append,
+        sortlist: MergeStrategy::Replace,
+    };
+
+    let (config, provenance) = Config::merge_layers(
+        [("distro", &distro), ("dhcp", &dhcp), ("vpn", &vpn)],
+        policy,
+    );
+    assert_eq!(
+        config.nameservers,
+        [ip("10.8.0.1"), ip("192.168.1.1"), ip("10.0.0.1")]
+    );
+    assert_eq!(
+        provenance.nameservers,
+        [
+            (ip("10.8.0.1"), "vpn".to_string()),
+            (ip("192.168.1.1"), "dhcp".to_string()),
+            (ip("10.0.0.1"), "distro".to_string()),
+        ]
+    );
+    assert_eq!(
+        provenance.search,
+        [
+            ("example.com".to_string(), "distro".to_string()),
+            ("home".to_string(), "dhcp".to_string()),
+            ("corp.example.com".to_string(), "vpn".to_string()),
+        ]
+    );
+    assert_eq!(provenance.domain.as_deref(), Some("vpn"));
+    assert_eq!(provenance.options["ndots"], "distro");
+    assert_eq!(provenance.options["timeout"], "dhcp");
+    assert_eq!(provenance.options["rotate"], "vpn");
+    assert_eq!(config.timeout, 1);
+
+    let (single, _) = Config::merge_layers([("distro", &distro)], policy);
+    assert_eq!(single, distro);
+    let (empty, provenance) = Config::merge_layers(Vec::<(&str, &Config)>::new(), policy);
+    assert_eq!(empty, Config::new());
+    assert!(provenance.nameservers.is_empty());
+}
+
+#[test]
+fn test_provenance_of_other_fields() {
+    let mut base =
+        Config::parse_with_errors(b"lookup file\nfamily inet4\noptions foo:1\nclient-id base").0;
+    base.extensions.insert("port".into(), "53".into());
+    let mut local =
+        Config::parse_with_errors(b"lookup bind file\noptions foo:2\nclient-id local").0;
+    local.extensions.insert("port".into(), "5353".into());
+
+    let (config, provenance) =
+        Config::merge_layers([("base", &base), ("local", &local)], MergePolicy::default());
+    assert_eq!(config.lookup, [Lookup::Bind, Lookup::File]);
+    assert_eq!(provenance.lookup.as_deref(), Some("local"));
+    assert_eq!(config.family, [Family::Inet4]);
+    assert_eq!(provenance.family.as_deref(), Some("base"));
+    assert_eq!(provenance.options["foo"], "local");
+    assert_eq!(provenance.unknown_directives["client-id"], "local");
+    assert_eq!(config.extensions["port"], "5353");
+    assert_eq!(provenance.extensions["port"], "local");
+
+    let (_, provenance) = Config::merge_layers([("local", &local)], MergePolicy::default());
+    assert_eq!(provenance.family, None);
+}
@@ -0,0 +1,86 @@
+extern crate resolv_conf;
+
+use resolv_conf::{LinkDnsTable, ResolvedConfError, ScopedIp};
+use std::path::Path;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn load_fixture() -> LinkDnsTable {
+    LinkDnsTable::load_from(
+        Path::new("tests/systemd/run/resolve/netif"),
+        Path::new("tests/systemd/run/netif/links"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_load_link_state() {
+    let table = load_fixture();
+    assert_eq!(
+        table.links.keys().copied().collect::<Vec<_>>(),
+        vec![2, 3, 4]
+    );
+
+    let wired = table.get(2).unwrap();
+    assert_eq!(wired.servers, vec![ip("192.168.1.1"), ip("fe80::1%2")]);
+    assert_eq!(
+        wired.search_domains().collect::<Vec<_>>(),
+        vec!["home.example.com"]
+    );
+    assert_eq!(wired.route_domains().count(), 0);
+    assert_eq!(wired.default_route, None);
+    assert!(wired.is_default_route());
+
+    // the resolved state wins over the networkd one
+    let vpn = table.get(3).unwrap();
+    assert_eq!(vpn.servers, vec![ip("10.1.1.1"), ip("10.1.1.2")]);
+    assert_eq!(
+        vpn.route_domains().collect::<Vec<_>>(),
+        vec!["corp.example.com", "1.10.in-addr.arpa"]
+    );
+    assert_eq!(vpn.default_route, Some(false));
+    assert!(!vpn.is_default_route());
+    assert_eq!(vpn.sources.len(), 2);
+
+    let unmanaged = table.get(4).unwrap();
+    assert!(unmanaged.servers.is_empty());
+}
+
+#[test]
+fn test_route() {
+    let table = load_fixture();
+    let route = |name: &str| {
+        table
+            .route(name)
+            .iter()
+            .map(|link| link.ifindex)
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(route("git.corp.example.com"), vec![3]);
+    assert_eq!(route("CORP.example.com."), vec![3]);
+    assert_eq!(route("nas.home.example.com"), vec![2]);
+    assert_eq!(route("www.example.org"), vec![2]);
+    assert_eq!(route("notcorp.example.com"), vec![2]);
+}
+
+#[test]
+fn test_missing_directories() {
+    let table = LinkDnsTable::load_from(Path::new("tests/missing"), Path::new("tests/missing"));
+    assert_eq!(table.unwrap(), LinkDnsTable::default());
+}
+
+#[test]
+fn test_invalid_state() {
+    let dir = tempfile::tempdir().unwrap();
+    std::fs::write(dir.path().join("5"), "SERVERS=10.0.0.1 bogus\n").unwrap();
+    match LinkDnsTable::load_from(dir.path(), Path::new("tests/missing")) {
+        Err(ResolvedConfError::InFile(path, err)) => {
+            assert_eq!(path, dir.path().join("5"));
+            assert!(matches!(*err, ResolvedConfError::InvalidServer(0, _)));
+        }
+        other => panic!("unexpected result: {other:?}"),
+    }
+}
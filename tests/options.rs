@@ -0,0 +1,179 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, ConfigRef, OptionParseError, ResolverOption};
+
+#[test]
+fn test_round_trip() {
+    for option in [
+        "debug",
+        "ndots:5",
+        "timeout:0",
+        "attempts:3",
+        "no-ip6-dotint",
+        "single-request-reopen",
+        "no-aaaa",
+        "strict-error",
+        "res_nquery_retries:4",
+    ] {
+        let parsed: ResolverOption = option.parse().unwrap();
+        assert_eq!(parsed.to_string(), option);
+    }
+    assert_eq!(ResolverOption::Ndots(2).name(), "ndots");
+    assert_eq!(
+        "ndots".parse::<ResolverOption>(),
+        Ok(ResolverOption::Unknown("ndots".into(), None))
+    );
+}
+
+#[test]
+fn test_parse_errors() {
+    assert_eq!("".parse::<ResolverOption>(), Err(OptionParseError::Empty));
+    assert_eq!(
+        "ndots:x".parse::<ResolverOption>(),
+        Err(OptionParseError::InvalidValue("ndots".into()))
+    );
+    assert_eq!(
+        ResolverOption::from_name_value("timeout", Some("-1")),
+        Err(OptionParseError::InvalidValue("timeout".into()))
+    );
+    assert_eq!(
+        OptionParseError::InvalidValue("ndots".into()).to_string(),
+        "invalid value for option ndots"
+    );
+}
+
+#[test]
+fn test_kubernetes_options() {
+    // dnsConfig.options of a pod spec
+    let options = [
+        ("ndots", Some("2")),
+        ("edns0", None),
+        ("single-request", None),
+    ];
+    let options = options
+        .iter()
+        .map(|&(name, value)| ResolverOption::from_name_value(name, value))
+        .collect::<Result<Vec<_>, _>>()
+        .unwrap();
+    assert_eq!(
+        options,
+        [
+            ResolverOption::Ndots(2),
+            ResolverOption::Edns0,
+            ResolverOption::SingleRequest,
+        ]
+    );
+}
+
+#[test]
+fn test_options_in_source_order() {
+    let buf = "options ndots:1 no-ip6-dotint\nnameserver 10.0.0.1\noptions ndots:3 rotate ndots:2";
+    let config = Config::parse_with_errors(buf.as_bytes()).0;
+    let expected = [
+        ResolverOption::Ndots(1),
+        ResolverOption::NoIp6Dotint,
+        ResolverOption::Ndots(3),
+        ResolverOption::Rotate,
+        ResolverOption::Ndots(2),
+    ];
+    assert_eq!(config.options(), expected);
+    assert_eq!(config.ndots, 2);
+    assert_eq!(
+        ConfigRef::parse(buf).unwrap().options().collect::<Vec<_>>(),
+        expected
+    );
+
+    // unknown options are kept in place, invalid values are not
+    let config = Config::parse_with_errors(b"options strict-error ndots:x edns0").0;
+    assert_eq!(
+        config.options(),
+        [
+            ResolverOption::Unknown("strict-error".into(), None),
+            ResolverOption::Edns0,
+        ]
+    );
+
+    // the order of the options is not compared
+    assert_eq!(
+        Config::parse("options rotate edns0").unwrap(),
+        Config::parse("options edns0 rotate").unwrap()
+    );
+}
+
+#[test]
+fn test_res_options() {
+    let mut config = Config::parse("options ndots:2 attempts:3").unwrap();
+    config.apply_res_options("ndots:5 use-vc debug:1").unwrap();
+    assert_eq!(config.ndots, 5);
+    assert_eq!(config.attempts, 3);
+    assert!(config.use_vc);
+    assert!(config.debug);
+    assert_eq!(config.options().len(), 5);
+
+    let before = config.clone();
+    assert_eq!(
+        config.apply_res_options("rotate timeout:x"),
+        Err(OptionParseError::InvalidValue("timeout".into()))
+    );
+    assert_eq!(config, before);
+    assert!(!config.rotate);
+
+    config.apply_res_options("res_retries:4").unwrap();
+    assert_eq!(
+        config.unknown_options,
+        [("res_retries".to_string(), Some("4".to_string()))]
+    );
+}
+
+#[test]
+fn test_explicit_defaults() {
+    let config = Config::parse("options ndots:1 timeout:5 attempts:2 no-ip6-dotint").unwrap();
+    assert_eq!(config, Config::new());
+    assert_eq!(config.explicit_ndots(), Some(1));
+    assert_eq!(config.explicit_timeout(), Some(5));
+    assert_eq!(config.explicit_attempts(), Some(2));
+    assert_eq!(config.explicit_ip6_dotint(), Some(false));
+    assert!(config.is_explicit("no-ip6-dotint"));
+    assert!(!config.is_explicit("ip6-dotint"));
+
+    let defaults = Config::new();
+    assert_eq!(defaults.explicit_ndots(), None);
+    assert_eq!(defaults.explicit_ip6_dotint(), None);
+    assert!(!defaults.is_explicit("ndots"));
+
+    // explicit defaults survive a round-trip
+    let output = config.to_string();
+    assert_eq!(
+        output,
+        "options ndots:1\noptions timeout:5\noptions attempts:2\noptions no-ip6-dotint\n"
+    );
+    let restored = Config::parse(&output).unwrap();
+    assert_eq!(restored.explicit_ndots(), Some(1));
+    assert_eq!(restored.explicit_ip6_dotint(), Some(false));
+    assert_eq!(Config::new().to_string(), "");
+}
+
+#[test]
+fn test_fields_written_directly() {
+    let mut config = Config::parse_with_errors(b"options rotate ndots:1 foo:1").0;
+    config.rotate = false;
+    config.ndots = 4;
+    config.unknown_options.clear();
+    assert!(!config.is_explicit("rotate"));
+    assert!(!config.is_explicit("foo"));
+    assert_eq!(config.options(), [ResolverOption::Ndots(4)]);
+    assert_eq!(config.to_string(), "options ndots:4\n");
+
+    assert_eq!(config.explicit_ndots(), None);
+
+    // the parsed option is in effect again once the field agrees with it
+    config.ndots = 1;
+    assert_eq!(config.explicit_ndots(), Some(1));
+    assert_eq!(config.to_string(), "options ndots:1\n");
+
+    let mut config = Config::parse("options no-ip6-dotint").unwrap();
+    config.ip6_dotint = true;
+    assert_eq!(config.explicit_ip6_dotint(), None);
+    assert_eq!(config.options(), [ResolverOption::Ip6Dotint]);
+    assert_eq!(config.to_string(), "options ip6-dotint\n");
+}
@@ -0,0 +1,172 @@
+extern crate resolv_conf;
+
+use resolv_conf::{
+    visit, Config, Family, Network, ParseError, Parser, ResolverOption, ScopedIp, Visitor,
+};
+use std::fs;
+
+/// Records every event as a line of text
+#[derive(Default)]
+struct Recorder {
+    events: Vec<String>,
+    handle_unknown: bool,
+}
+
+impl<'a> Visitor<'a> for Recorder {
+    fn comment(&mut self, lineno: usize, text: &'a [u8]) {
+        let text = String::from_utf8_lossy(text);
+        self.events.push(format!("{lineno} comment {text}"));
+    }
+
+    fn nameserver(&mut self, lineno: usize, ip: ScopedIp, _: &'a str) {
+        self.events.push(format!("{lineno} nameserver {ip}"));
+    }
+
+    fn domain(&mut self, lineno: usize, domain: &'a str) {
+        self.events.push(format!("{lineno} domain {domain}"));
+    }
+
+    fn search(&mut self, lineno: usize, domains: &'a str) {
+        self.events
+            .push(format!("{lineno} search {}", domains.trim()));
+    }
+
+    fn sortlist(&mut self, lineno: usize, _: &'a str) {
+        self.events.push(format!("{lineno} sortlist"));
+    }
+
+    fn network(&mut self, lineno: usize, network: Network, _: &'a str) {
+        self.events.push(format!("{lineno} network {network}"));
+    }
+
+    fn option(&mut self, lineno: usize, option: ResolverOption, _: &'a str) {
+        self.events.push(format!("{lineno} option {option:?}"));
+    }
+
+    fn lookup(&mut self, lineno: usize, database: &'a str) {
+        self.events.push(format!("{lineno} lookup {database}"));
+    }
+
+    fn family(&mut self, lineno: usize, family: Family, _: &'a str) {
+        self.events.push(format!("{lineno} family {family:?}"));
+    }
+
+    fn unknown_directive(&mut self, lineno: usize, keyword: &'a str, args: &'a str) -> bool {
+        self.events
+            .push(format!("{lineno} directive {keyword} {}", args.trim()));
+        self.handle_unknown
+    }
+
+    fn unknown_option(&mut self, lineno: usize, name: &'a str, value: Option<&'a str>) -> bool {
+        self.events
+            .push(format!("{lineno} unknown {name} {value:?}"));
+        self.handle_unknown
+    }
+
+    fn error(&mut self, error: ParseError) {
+        self.events.push(format!("error {error}"));
+    }
+}
+
+#[test]
+fn test_visitor_events() {
+    let buf = b"# head
+nameserver 10.0.0.1 # trailing
+domain example.com
+search a.example b.example
+sortlist 130.155.160.0/255.255.240.0 bad
+options ndots:2 rotate retrans:3
+lookup file bind
+family inet6
+port 5353
+";
+    let mut recorder = Recorder::default();
+    visit(buf, &mut recorder);
+    assert_eq!(
+        recorder.events,
+        vec![
+            "0 comment  head",
+            "1 nameserver 10.0.0.1",
+            "2 domain example.com",
+            "3 search a.example b.example",
+            "4 sortlist",
+            "4 network 130.155.160.0/255.255.240.0",
+            "error directive at line 4 contains invalid IP: invalid IP address syntax",
+            "5 option Ndots(2)",
+            "5 option Rotate",
+            "5 unknown retrans Some(\"3\")",
+            "error option at line 5 is not recognized",
+            "6 lookup file",
+            "6 lookup bind",
+            "7 family Inet6",
+            "8 directive port 5353",
+            "error directive at line 8 is not recognized",
+        ]
+    );
+
+    let mut recorder = Recorder {
+        handle_unknown: true,
+        ..Recorder::default()
+    };
+    visit(b"options retrans:3\nport 5353", &mut recorder);
+    assert_eq!(
+        recorder.events,
+        vec!["0 unknown retrans Some(\"3\")", "1 directive port 5353"]
+    );
+}
+
+fn solaris_parser() -> Parser {
+    Parser::new()
+        .option("retrans", |lineno, value, extensions| {
+            let value = value.ok_or(ParseError::InvalidOptionValue(lineno))?;
+            value
+                .parse::<u32>()
+                .map_err(|_| ParseError::InvalidOptionValue(lineno))?;
+            extensions.insert("retrans".into(), value.into());
+            Ok(())
+        })
+        .directive("port", |lineno, args, extensions| {
+            let mut words = args.split_whitespace();
+            let port = words.next().ok_or(ParseError::InvalidValue(lineno))?;
+            if words.next().is_some() {
+                return Err(ParseError::ExtraData(lineno));
+            }
+            extensions.insert("port".into(), port.into());
+            Ok(())
+        })
+        // built-in directives can not be overridden
+        .directive("nameserver", |_, _, _| Err(ParseError::InvalidValue(0)))
+}
+
+#[test]
+fn test_parser_extensions() {
+    let parser = solaris_parser();
+    let config = parser
+        .parse("nameserver 10.0.0.1\noptions retrans:3 rotate\nport 5353\n")
+        .unwrap();
+    assert_eq!(config.extensions.len(), 2);
+    assert_eq!(config.extensions["retrans"], "3");
+    assert_eq!(config.extensions["port"], "5353");
+    assert!(config.rotate);
+    assert_eq!(config.nameservers, vec!["10.0.0.1".parse().unwrap()]);
+    // extensions are not written back
+    assert_eq!(config.to_string(), "nameserver 10.0.0.1\noptions rotate\n");
+    assert_eq!(config.options(), vec![ResolverOption::Rotate]);
+
+    let (config, errors) = parser.parse_with_errors(b"port 53 54\noptions retrans:x\nfoo bar\n");
+    assert!(config.extensions.is_empty());
+    assert_eq!(
+        format!("{:?}", errors),
+        "[ExtraData(0), InvalidOptionValue(1), InvalidDirective(2)]"
+    );
+}
+
+#[test]
+fn test_parser_without_handlers() {
+    let buf = fs::read("tests/resolv.conf-linux").unwrap();
+    assert_eq!(
+        Parser::new().parse(&buf).unwrap(),
+        Config::parse(&buf).unwrap()
+    );
+    assert!(Parser::new().parse("port 5353").is_err());
+}
@@ -0,0 +1,142 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, Limit, LoadError, ParseError, ParseLimits};
+use std::fs;
+use std::io::{self, BufReader, Read};
+
+fn limit_exceeded<R: Read>(reader: R, limits: &ParseLimits) -> (usize, Limit) {
+    match Config::parse_reader(BufReader::new(reader), limits) {
+        Err(LoadError::Parse(None, ParseError::LimitExceeded(line, limit))) => (line, limit),
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_same_as_parse_with_errors() {
+    let mut files = vec![
+        "tests/resolv.conf-simple",
+        "tests/resolv.conf-linux",
+        "tests/resolv.conf-macos",
+        "tests/resolv.conf-openbsd",
+    ]
+    .into_iter()
+    .map(|path| fs::read(path).unwrap())
+    .collect::<Vec<_>>();
+    files.push(
+        b"nameserver 10.0.0.1\ninvalid foo.com\noptions ndots:x\n\xff\xfe\n# \xff\n".to_vec(),
+    );
+    files.push(b"nameserver 10.0.0.1".to_vec());
+    files.push(Vec::new());
+
+    for buf in files {
+        let expected = Config::parse_with_errors(&buf);
+        // a tiny buffer makes lines span several reads
+        let reader = BufReader::with_capacity(3, &buf[..]);
+        let actual = Config::parse_reader_with_errors(reader, &ParseLimits::default()).unwrap();
+        assert_eq!(actual.0, expected.0);
+        assert_eq!(format!("{:?}", actual.1), format!("{:?}", expected.1));
+    }
+}
+
+#[test]
+fn test_first_error() {
+    let buf = &b"nameserver 10.0.0.1\ninvalid foo.com\n"[..];
+    match Config::parse_reader(buf, &ParseLimits::default()) {
+        Err(LoadError::Parse(None, ParseError::InvalidDirective(1))) => {}
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[test]
+fn test_unbounded_input() {
+    let limits = ParseLimits::default();
+    assert_eq!(
+        limit_exceeded(io::repeat(b'a'), &limits),
+        (0, Limit::LineLength)
+    );
+    assert_eq!(
+        limit_exceeded(io::repeat(b'\n'), &limits),
+        (10_000, Limit::Lines)
+    );
+    assert_eq!(
+        limit_exceeded(io::repeat(b'#').take(2_000_000), &limits),
+        (0, Limit::LineLength)
+    );
+
+    let limits = ParseLimits {
+        max_bytes: 100,
+        ..ParseLimits::default()
+    };
+    let comments = io::repeat(b'#').take(4).chain(io::repeat(b'\n')).take(1000);
+    assert_eq!(limit_exceeded(comments, &limits), (96, Limit::Bytes));
+}
+
+#[test]
+fn test_list_limits() {
+    let limits = ParseLimits {
+        max_nameservers: 2,
+        max_search: 2,
+        max_sortlist: 1,
+        ..ParseLimits::default()
+    };
+
+    let buf = &b"nameserver 10.0.0.1\nnameserver 10.0.0.2\nnameserver 10.0.0.3\n"[..];
+    assert_eq!(limit_exceeded(buf, &limits), (2, Limit::Nameservers));
+
+    let buf = &b"search a.example b.example\nsearch a c d\n"[..];
+    assert_eq!(limit_exceeded(buf, &limits), (1, Limit::Search));
+
+    let buf = &b"sortlist 10.0.0.0/255.0.0.0 192.168.0.0/255.255.0.0\n"[..];
+    assert_eq!(limit_exceeded(buf, &limits), (0, Limit::Sortlist));
+
+    let buf =
+        &b"nameserver 10.0.0.1\nsearch a.example b.example\nsortlist 10.0.0.0/255.0.0.0\n"[..];
+    let config = Config::parse_reader(buf, &limits).unwrap();
+    assert_eq!(config.nameservers.len(), 1);
+}
+
+#[test]
+fn test_limit_display() {
+    let err = ParseError::LimitExceeded(3, Limit::LineLength);
+    assert_eq!(err.to_string(), "line length limit exceeded at line 3");
+}
+
+#[test]
+fn test_limit_with_errors() {
+    let limits = ParseLimits {
+        max_nameservers: 1,
+        ..ParseLimits::default()
+    };
+    let buf =
+        &b"nameserver 10.0.0.1\ninvalid foo.com\nnameserver 10.0.0.2\nnameserver 10.0.0.3\n"[..];
+    let (config, errors) = Config::parse_reader_with_errors(buf, &limits).unwrap();
+    match &errors[..] {
+        [ParseError::InvalidDirective(1), ParseError::LimitExceeded(2, Limit::Nameservers)] => {}
+        other => panic!("unexpected errors: {:?}", other),
+    }
+    // reading stopped at the line exceeding the limit
+    assert_eq!(config.nameservers.len(), 2);
+}
+
+#[test]
+fn test_exactly_max_lines() {
+    let limits = ParseLimits {
+        max_lines: 2,
+        ..ParseLimits::default()
+    };
+    for buf in [
+        &b"nameserver 10.0.0.1\nnameserver 10.0.0.2\n"[..],
+        &b"nameserver 10.0.0.1\nnameserver 10.0.0.2"[..],
+    ] {
+        let config = Config::parse_reader(buf, &limits).unwrap();
+        assert_eq!(config.nameservers.len(), 2);
+    }
+    assert_eq!(
+        limit_exceeded(
+            &b"nameserver 10.0.0.1\n\nnameserver 10.0.0.2\n"[..],
+            &limits
+        ),
+        (2, Limit::Lines)
+    );
+    assert_eq!(limit_exceeded(&b"\n\n\n"[..], &limits), (2, Limit::Lines));
+}
@@ -0,0 +1,106 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{Config, LoadError, ScopedIp, SystemConfig};
+use std::fs;
+use std::thread;
+use std::time::Duration;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_reload_on_change() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+
+    let system = SystemConfig::open(&path)
+        .unwrap()
+        .with_interval(Duration::from_secs(0));
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+
+    // replace the file the way most tools do, with a rename
+    let tmp = dir.path().join("resolv.conf.tmp");
+    fs::write(&tmp, "nameserver 10.0.0.2\nnameserver 10.0.0.3\n").unwrap();
+    fs::rename(&tmp, &path).unwrap();
+
+    let clone = system.clone();
+    let handle = thread::spawn(move || clone.current());
+    assert_eq!(
+        handle.join().unwrap().nameservers,
+        vec![ip("10.0.0.2"), ip("10.0.0.3")]
+    );
+    assert_eq!(
+        system.cached().nameservers,
+        vec![ip("10.0.0.2"), ip("10.0.0.3")]
+    );
+}
+
+#[test]
+fn test_keep_last_good_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let system = SystemConfig::open(&path)
+        .unwrap()
+        .with_interval(Duration::from_secs(0));
+
+    fs::write(&path, "nameserver not-an-ip\n").unwrap();
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+    assert!(matches!(
+        *system.last_error().unwrap(),
+        LoadError::Parse(Some(_), _)
+    ));
+
+    fs::remove_file(&path).unwrap();
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+    assert!(matches!(
+        *system.last_error().unwrap(),
+        LoadError::NotFound(_)
+    ));
+
+    fs::write(&path, "nameserver 10.0.0.4\n").unwrap();
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.4")]);
+    assert!(system.last_error().is_none());
+}
+
+#[test]
+fn test_interval() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let system = SystemConfig::open(&path).unwrap();
+
+    fs::write(&path, "nameserver 10.0.0.22\n").unwrap();
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+}
+
+#[test]
+fn test_no_reload() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\noptions no-reload\n").unwrap();
+    let system = SystemConfig::open(&path)
+        .unwrap()
+        .with_interval(Duration::from_secs(0));
+    assert!(!system.is_watching());
+
+    fs::write(&path, "nameserver 10.0.0.2\n").unwrap();
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+}
+
+#[test]
+fn test_with_config() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    let system = SystemConfig::with_config(&path, Config::new());
+    assert_eq!(*system.current(), Config::new());
+    assert!(system.last_error().is_some());
+
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let system = SystemConfig::with_config(&path, Config::new());
+    assert_eq!(system.current().nameservers, vec![ip("10.0.0.1")]);
+    assert_eq!(system.path(), path);
+}
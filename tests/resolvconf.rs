@@ -0,0 +1,145 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Resolvconf, ScopedIp};
+use std::path::Path;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn load_debian() -> Resolvconf {
+    Resolvconf::load_debian_from(
+        Path::new("tests/resolvconf/etc"),
+        Path::new("tests/resolvconf/run/interface"),
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_debian_interface_order() {
+    let resolvconf = load_debian();
+    // the empty eth1 record is ignored
+    assert_eq!(
+        resolvconf.ordered_interfaces(),
+        vec!["tun0.openvpn", "eth0.dhclient", "wlan0.dhclient"]
+    );
+
+    let mut resolvconf = Resolvconf::debian();
+    for name in &[
+        "ppp0",
+        "lo.dnsmasq",
+        "em1_2",
+        "lo.pdns",
+        "lo.bind",
+        "tap0",
+        "lo.inet",
+    ] {
+        resolvconf
+            .interfaces
+            .push((name.to_string(), String::new()));
+    }
+    // lo.pdns is only matched by the catch-all pattern
+    assert_eq!(
+        resolvconf.ordered_interfaces(),
+        vec![
+            "lo.inet",
+            "lo.dnsmasq",
+            "lo.bind",
+            "tap0",
+            "em1_2",
+            "ppp0",
+            "lo.pdns"
+        ]
+    );
+
+    resolvconf.interface_order = vec!["lo.*".into(), "eth*".into()];
+    assert_eq!(
+        resolvconf.ordered_interfaces(),
+        vec!["lo.bind", "lo.dnsmasq", "lo.inet", "lo.pdns"]
+    );
+}
+
+#[test]
+fn test_debian_generate() {
+    let output = load_debian().generate();
+    assert_eq!(
+        output.text,
+        "# Dynamic resolv.conf(5) file for glibc resolver(3) generated by resolvconf(8)
+#     DO NOT EDIT THIS FILE BY HAND -- YOUR CHANGES WILL BE OVERWRITTEN
+nameserver 10.1.1.1
+nameserver 192.168.1.1
+nameserver 192.168.1.2
+search base.example.com corp.example.com home
+options rotate
+# local additions
+options timeout:3
+"
+    );
+    assert!(output.errors.is_empty());
+    assert_eq!(
+        output.nameserver_sources,
+        vec![
+            (ip("10.1.1.1"), "tun0.openvpn".to_string()),
+            (ip("192.168.1.1"), "eth0.dhclient".to_string()),
+            (ip("192.168.1.2"), "eth0.dhclient".to_string()),
+        ]
+    );
+    assert_eq!(
+        output.config.nameservers,
+        vec![ip("10.1.1.1"), ip("192.168.1.1"), ip("192.168.1.2")]
+    );
+    assert!(output.config.rotate);
+    assert_eq!(output.config.timeout, 3);
+}
+
+#[test]
+fn test_truncate_after_loopback() {
+    let mut resolvconf = Resolvconf::debian();
+    resolvconf
+        .interfaces
+        .push(("eth0".into(), "nameserver 192.168.1.1".into()));
+    resolvconf
+        .interfaces
+        .push(("lo.dnsmasq".into(), "nameserver 127.0.0.1".into()));
+    assert_eq!(resolvconf.generate().text, "nameserver 127.0.0.1\n");
+
+    resolvconf.truncate_after_loopback = false;
+    assert_eq!(
+        resolvconf.generate().text,
+        "nameserver 127.0.0.1\nnameserver 192.168.1.1\n"
+    );
+}
+
+#[test]
+fn test_openresolv() {
+    let resolvconf = Resolvconf::load_openresolv_from(
+        Path::new("tests/resolvconf/resolvconf.conf"),
+        Path::new("tests/resolvconf/run/interface"),
+    )
+    .unwrap();
+    assert_eq!(
+        resolvconf.ordered_interfaces(),
+        vec!["tun0.openvpn", "eth0.dhclient", "wlan0.dhclient"]
+    );
+
+    let output = resolvconf.generate();
+    assert_eq!(
+        output.text,
+        "# Generated by resolvconf
+nameserver 127.0.0.1
+nameserver 192.168.1.1
+nameserver 192.168.1.2
+nameserver 172.16.0.1
+search corp.example.com home local.example.com
+options rotate
+"
+    );
+    assert_eq!(
+        output.nameserver_sources[0],
+        (ip("127.0.0.1"), "resolvconf.conf".to_string())
+    );
+    assert_eq!(
+        output.nameserver_sources[3],
+        (ip("172.16.0.1"), "wlan0.dhclient".to_string())
+    );
+}
@@ -0,0 +1,131 @@
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{
+    LoadError, ResolvedConfig, ResolvedEvidence, ResolvedMode, ResolvedPaths, ScopedIp,
+};
+use std::fs;
+use std::path::{Path, PathBuf};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+/// Lay out `run/systemd/resolve/{stub-resolv.conf,resolv.conf}` under `root`
+fn populate(root: &Path) -> ResolvedPaths {
+    let resolve = root.join("run/systemd/resolve");
+    fs::create_dir_all(&resolve).unwrap();
+    fs::create_dir_all(root.join("etc")).unwrap();
+    fs::copy(
+        "tests/resolv.conf-systemd-stub",
+        resolve.join("stub-resolv.conf"),
+    )
+    .unwrap();
+    fs::copy(
+        "tests/resolv.conf-systemd-uplink",
+        resolve.join("resolv.conf"),
+    )
+    .unwrap();
+    ResolvedPaths {
+        resolv_conf: root.join("etc/resolv.conf"),
+        uplink: resolve.join("resolv.conf"),
+    }
+}
+
+#[cfg(unix)]
+#[test]
+fn test_stub_symlink() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+    let target = PathBuf::from("../run/systemd/resolve/stub-resolv.conf");
+    std::os::unix::fs::symlink(&target, &paths.resolv_conf).unwrap();
+
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Stub);
+    assert_eq!(resolved.evidence, ResolvedEvidence::Symlink(target));
+    assert_eq!(resolved.config.nameservers, vec![ip("127.0.0.53")]);
+    assert!(resolved.config.trust_ad);
+    assert_eq!(
+        resolved.effective().nameservers,
+        vec![ip("10.0.0.1"), ip("2001:db8::1")]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_uplink_symlink() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+    std::os::unix::fs::symlink(&paths.uplink, &paths.resolv_conf).unwrap();
+
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Uplink);
+    assert_eq!(resolved.upstream, None);
+    assert_eq!(
+        resolved.effective().nameservers,
+        vec![ip("10.0.0.1"), ip("2001:db8::1")]
+    );
+}
+
+#[test]
+fn test_stub_header() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+    fs::copy("tests/resolv.conf-systemd-stub", &paths.resolv_conf).unwrap();
+
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Stub);
+    assert!(matches!(resolved.evidence, ResolvedEvidence::Header(_)));
+    assert!(resolved.upstream.is_some());
+}
+
+#[test]
+fn test_stub_nameserver() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+
+    fs::write(&paths.resolv_conf, "nameserver 127.0.0.54\n").unwrap();
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Static);
+    assert_eq!(resolved.evidence, ResolvedEvidence::StubNameserver);
+
+    fs::write(&paths.resolv_conf, "nameserver 127.0.0.53\nsearch .\n").unwrap();
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Stub);
+
+    fs::remove_file(&paths.uplink).unwrap();
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.upstream, None);
+    assert_eq!(resolved.effective().nameservers, vec![ip("127.0.0.53")]);
+}
+
+#[test]
+fn test_foreign() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+    fs::copy("tests/resolv.conf-simple", &paths.resolv_conf).unwrap();
+
+    let resolved = ResolvedConfig::load_from(&paths).unwrap();
+    assert_eq!(resolved.mode, ResolvedMode::Foreign);
+    assert_eq!(resolved.evidence, ResolvedEvidence::None);
+    assert_eq!(resolved.upstream, None);
+    assert_eq!(
+        resolved.effective().nameservers,
+        vec![ip("8.8.8.8"), ip("8.8.4.4")]
+    );
+}
+
+#[test]
+fn test_errors() {
+    let root = tempfile::tempdir().unwrap();
+    let paths = populate(root.path());
+    match ResolvedConfig::load_from(&paths) {
+        Err(LoadError::NotFound(path)) => assert_eq!(path, paths.resolv_conf),
+        other => panic!("unexpected {other:?}"),
+    }
+
+    fs::write(&paths.resolv_conf, "nameserver 127.0.0.53\nbogus\n").unwrap();
+    let err = ResolvedConfig::load_from(&paths).unwrap_err();
+    assert!(matches!(err, LoadError::Parse(..)));
+    assert_eq!(err.path(), Some(paths.resolv_conf.as_path()));
+}
@@ -0,0 +1,134 @@
+extern crate resolv_conf;
+
+use resolv_conf::{
+    DnsOverTls, Dnssec, ResolveSupport, ResolvedConf, ResolvedConfError, ResolvedDomain,
+    ResolvedServer, ScopedIp, StubListener,
+};
+use std::path::{Path, PathBuf};
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn server(s: &str) -> ResolvedServer {
+    s.parse().unwrap()
+}
+
+fn load_fixture() -> ResolvedConf {
+    ResolvedConf::load_from(
+        Path::new("tests/systemd/etc/resolved.conf"),
+        &[
+            "tests/systemd/etc/resolved.conf.d",
+            "tests/systemd/usr-lib/resolved.conf.d",
+            "tests/systemd/missing",
+        ],
+    )
+    .unwrap()
+}
+
+#[test]
+fn test_server_syntax() {
+    let parsed = server("10.1.1.1:5353%tun0#ns.corp.example.com");
+    assert_eq!(parsed.ip, "10.1.1.1".parse::<std::net::IpAddr>().unwrap());
+    assert_eq!(parsed.port, Some(5353));
+    assert_eq!(parsed.interface.as_deref(), Some("tun0"));
+    assert_eq!(parsed.server_name.as_deref(), Some("ns.corp.example.com"));
+    assert_eq!(parsed.to_string(), "10.1.1.1:5353%tun0#ns.corp.example.com");
+
+    let parsed = server("[2001:db8::53]:53%tun0");
+    assert_eq!(parsed.port, Some(53));
+    assert_eq!(parsed.to_scoped_ip(), ip("2001:db8::53%tun0"));
+    assert_eq!(parsed.to_string(), "[2001:db8::53]:53%tun0");
+
+    let parsed = server("fe80::1%2");
+    assert_eq!(parsed.port, None);
+    assert_eq!(parsed.to_scoped_ip(), ip("fe80::1%2"));
+
+    assert!("2001:db8::53]:53".parse::<ResolvedServer>().is_err());
+    assert!("[2001:db8::53]53".parse::<ResolvedServer>().is_err());
+    assert!("10.0.0.1:dns".parse::<ResolvedServer>().is_err());
+    assert!("10.0.0.1#".parse::<ResolvedServer>().is_err());
+}
+
+#[test]
+fn test_parse_single_file() {
+    let conf = ResolvedConf::parse(
+        "[Network]\nDNS=192.0.2.1\n[Resolve]\nDNS=1.1.1.1\nDNS=9.9.9.9\nDNSSEC=true\n",
+    )
+    .unwrap();
+    assert_eq!(conf.dns, vec![server("1.1.1.1"), server("9.9.9.9")]);
+    assert_eq!(conf.dnssec, Some(Dnssec::Yes));
+    assert_eq!(conf.fallback_dns, None);
+
+    assert!(matches!(
+        ResolvedConf::parse("[Resolve]\nDNSSEC=maybe"),
+        Err(ResolvedConfError::InvalidValue(1))
+    ));
+    assert!(matches!(
+        ResolvedConf::parse("[Resolve]\nDNS"),
+        Err(ResolvedConfError::InvalidLine(1))
+    ));
+    assert!(matches!(
+        ResolvedConf::parse("[Resolve]\nDNS=not-an-ip"),
+        Err(ResolvedConfError::InvalidServer(1, _))
+    ));
+}
+
+#[test]
+fn test_dropin_merging() {
+    let conf = load_fixture();
+    assert_eq!(
+        conf.sources,
+        vec![
+            PathBuf::from("tests/systemd/etc/resolved.conf"),
+            PathBuf::from("tests/systemd/usr-lib/resolved.conf.d/10-vendor.conf"),
+            PathBuf::from("tests/systemd/etc/resolved.conf.d/20-corp.conf"),
+            PathBuf::from("tests/systemd/etc/resolved.conf.d/30-privacy.conf"),
+        ]
+    );
+
+    // the empty assignment in 20-corp.conf drops the servers of the main file
+    assert_eq!(
+        conf.dns,
+        vec![
+            server("10.1.1.1:5353%tun0#ns.corp.example.com"),
+            server("[2001:db8::53]:53%tun0"),
+        ]
+    );
+    assert_eq!(conf.fallback_dns, Some(vec![]));
+    assert_eq!(
+        conf.domains,
+        vec![
+            ResolvedDomain {
+                name: "example.com".into(),
+                route_only: false
+            },
+            ResolvedDomain {
+                name: "corp.example.com".into(),
+                route_only: true
+            },
+        ]
+    );
+    assert_eq!(conf.dnssec, Some(Dnssec::AllowDowngrade));
+    assert_eq!(conf.dns_over_tls, Some(DnsOverTls::Opportunistic));
+    assert_eq!(conf.llmnr, Some(ResolveSupport::No));
+    // drop-ins are applied after the main file, even vendor ones
+    assert_eq!(conf.multicast_dns, Some(ResolveSupport::Resolve));
+    assert_eq!(conf.dns_stub_listener, Some(StubListener::Udp));
+    assert_eq!(conf.other.get("Cache").map(String::as_str), Some("yes"));
+}
+
+#[test]
+fn test_to_config() {
+    let config = load_fixture().to_config();
+    assert_eq!(
+        config.nameservers,
+        vec![ip("10.1.1.1"), ip("2001:db8::53%tun0")]
+    );
+    assert_eq!(config.get_search(), Some(&vec!["example.com".to_string()]));
+
+    let conf = ResolvedConf::parse("[Resolve]\nFallbackDNS=8.8.8.8\nDomains=~.").unwrap();
+    let config = conf.to_config();
+    assert_eq!(config.nameservers, vec![ip("8.8.8.8")]);
+    assert_eq!(config.get_search(), None);
+}
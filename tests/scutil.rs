@@ -0,0 +1,96 @@
+extern crate resolv_conf;
+
+use resolv_conf::{ScopedIp, ScutilDns, ScutilParseError};
+use std::fs;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn parse_fixture() -> ScutilDns {
+    let data = fs::read_to_string("tests/scutil-dns-macos").unwrap();
+    ScutilDns::parse(&data).unwrap()
+}
+
+#[test]
+fn test_parse_scutil_dns() {
+    let dns = parse_fixture();
+    assert_eq!(dns.resolvers.len(), 3);
+    assert_eq!(dns.scoped_resolvers.len(), 1);
+
+    let primary = &dns.resolvers[0];
+    assert_eq!(primary.number, 1);
+    assert_eq!(
+        primary.nameservers,
+        vec![ip("192.168.1.1"), ip("fe80::1%en0")]
+    );
+    assert_eq!(primary.search, vec!["corp.example.com", "example.com"]);
+    assert_eq!(primary.domain, None);
+    assert_eq!(primary.if_index, Some(6));
+    assert_eq!(primary.if_name.as_deref(), Some("en0"));
+    assert_eq!(
+        primary.flags,
+        vec!["Request A records", "Request AAAA records"]
+    );
+    assert_eq!(primary.reach, Some(0x0002_0002));
+    assert_eq!(
+        primary.reach_description,
+        vec!["Reachable", "Directly Reachable Address"]
+    );
+    assert!(!primary.is_per_domain());
+
+    let mdns = &dns.resolvers[1];
+    assert_eq!(mdns.domain.as_deref(), Some("local"));
+    assert_eq!(mdns.options, vec!["mdns"]);
+    assert_eq!(mdns.timeout, Some(5));
+    assert_eq!(mdns.order, Some(300_000));
+    assert!(mdns.nameservers.is_empty());
+
+    let corp = &dns.resolvers[2];
+    assert!(corp.is_per_domain());
+    assert_eq!(corp.port, Some(5353));
+    assert_eq!(corp.nameservers, vec![ip("10.1.1.1"), ip("10.1.1.2")]);
+
+    let scoped = &dns.scoped_resolvers[0];
+    assert!(scoped.is_scoped());
+    assert_eq!(scoped.if_index, Some(6));
+}
+
+#[test]
+fn test_scutil_to_config() {
+    let dns = parse_fixture();
+
+    let config = dns.resolvers[0].to_config();
+    assert_eq!(
+        config.nameservers,
+        vec![ip("192.168.1.1"), ip("fe80::1%en0")]
+    );
+    assert_eq!(
+        config.get_last_search_or_domain().collect::<Vec<_>>(),
+        vec!["corp.example.com", "example.com"]
+    );
+
+    let config = dns.resolvers[1].to_config();
+    assert_eq!(config.get_domain().map(String::as_str), Some("local"));
+    assert_eq!(config.timeout, 5);
+}
+
+#[test]
+fn test_scutil_errors() {
+    assert_eq!(
+        ScutilDns::parse("nameserver[0] : 10.0.0.1"),
+        Err(ScutilParseError::UnexpectedLine(0))
+    );
+    assert!(matches!(
+        ScutilDns::parse("resolver #1\n  nameserver[0] : 10.0.0.1%en0"),
+        Err(ScutilParseError::InvalidIp(1, _))
+    ));
+    assert_eq!(
+        ScutilDns::parse("resolver #1\n  port : http"),
+        Err(ScutilParseError::InvalidValue(1))
+    );
+    assert_eq!(
+        ScutilDns::parse("No DNS configuration available\n"),
+        Ok(ScutilDns::default())
+    );
+}
@@ -0,0 +1,148 @@
+#![cfg(feature = "serde")]
+
+extern crate resolv_conf;
+extern crate serde_json;
+
+use resolv_conf::{Config, Family, Lookup, Network, ResolverOption, ScopedIp};
+use serde_json::json;
+use std::fs;
+
+#[test]
+fn test_round_trip_fixtures() {
+    for path in [
+        "tests/resolv.conf-simple",
+        "tests/resolv.conf-linux",
+        "tests/resolv.conf-macos",
+        "tests/resolv.conf-openbsd",
+        "tests/resolv.conf-systemd-stub",
+        "tests/resolv.conf-systemd-uplink",
+    ] {
+        let config = Config::parse_with_errors(&fs::read(path).unwrap()).0;
+        let json = serde_json::to_string(&config).unwrap();
+        let parsed: Config = serde_json::from_str(&json).unwrap();
+        assert_eq!(parsed, config, "{path}");
+        assert_eq!(parsed.to_string(), config.to_string(), "{path}");
+        assert_eq!(parsed.options(), config.options(), "{path}");
+    }
+}
+
+#[test]
+fn test_schema() {
+    let config = Config::parse(
+        "nameserver fe80::1%eth0\ndomain example.com\nsearch a.example\n\
+         sortlist 10.0.0.0/255.0.0.0 10.1.0.0/255.0.255.0\noptions ndots:1 rotate\n\
+         lookup file bind\nfamily inet6",
+    )
+    .unwrap();
+    assert_eq!(
+        serde_json::to_value(&config).unwrap(),
+        json!({
+            "nameservers": ["fe80::1%eth0"],
+            "last_search": "search",
+            "domain": "example.com",
+            "search": ["a.example"],
+            "sortlist": ["10.0.0.0/8", "10.1.0.0/255.0.255.0"],
+            "debug": false,
+            "ndots": 1,
+            "timeout": 5,
+            "attempts": 2,
+            "rotate": true,
+            "no_check_names": false,
+            "inet6": false,
+            "ip6_bytestring": false,
+            "ip6_dotint": false,
+            "edns0": false,
+            "single_request": false,
+            "single_request_reopen": false,
+            "no_tld_query": false,
+            "use_vc": false,
+            "no_reload": false,
+            "trust_ad": false,
+            "lookup": ["file", "bind"],
+            "family": ["inet6"],
+            "no_aaaa": false,
+            "unknown_options": [],
+            "unknown_directives": [],
+            "extensions": {},
+            "options": ["ndots:1", "rotate"],
+        })
+    );
+
+    // missing fields have their default value
+    let config: Config =
+        serde_json::from_value(json!({"nameservers": ["10.0.0.1"], "ndots": 3})).unwrap();
+    assert_eq!(config.to_string(), "nameserver 10.0.0.1\noptions ndots:3\n");
+
+    // the fields win over the options they disagree with
+    let config: Config = serde_json::from_value(json!({
+        "ndots": 2,
+        "options": ["ndots:4", "rotate", "foo:1"],
+    }))
+    .unwrap();
+    assert_eq!((config.ndots, config.rotate), (2, false));
+    assert_eq!(config.options(), [ResolverOption::Ndots(2)]);
+    assert!(config.unknown_options.is_empty());
+    assert_eq!(config.to_string(), "options ndots:2\n");
+
+    assert!(serde_json::from_value::<Config>(json!({"options": ["ndots:x"]})).is_err());
+}
+
+#[test]
+fn test_explicit_defaults() {
+    let config = Config::parse("options ndots:1 no-ip6-dotint rotate").unwrap();
+    let json = serde_json::to_value(&config).unwrap();
+    assert_eq!(
+        json["options"],
+        json!(["ndots:1", "no-ip6-dotint", "rotate"])
+    );
+
+    let parsed: Config = serde_json::from_value(json).unwrap();
+    assert_eq!(parsed, config);
+    assert_eq!(parsed.options(), config.options());
+    assert_eq!(parsed.explicit_ndots(), Some(1));
+    assert_eq!(parsed.explicit_ip6_dotint(), Some(false));
+    assert_eq!(parsed.to_string(), config.to_string());
+    assert!(parsed.to_string().contains("options no-ip6-dotint\n"));
+}
+
+#[test]
+fn test_types() {
+    let ip: ScopedIp = serde_json::from_str("\"fe80::1%eth0\"").unwrap();
+    assert_eq!(
+        ip,
+        ScopedIp::V6("fe80::1".parse().unwrap(), Some("eth0".into()))
+    );
+    assert!(serde_json::from_str::<ScopedIp>("\"10.0.0.1%eth0\"").is_err());
+
+    let v4 = |address: [u8; 4], mask: [u8; 4]| Network::V4(address.into(), mask.into());
+    for (text, network) in [
+        ("10.0.0.0/8", v4([10, 0, 0, 0], [255, 0, 0, 0])),
+        ("10.0.0.0/255.0.0.0", v4([10, 0, 0, 0], [255, 0, 0, 0])),
+        ("0.0.0.0/0", v4([0, 0, 0, 0], [0, 0, 0, 0])),
+        ("10.0.0.1/32", v4([10, 0, 0, 1], [255, 255, 255, 255])),
+        (
+            "fe80::/64",
+            Network::V6(
+                "fe80::".parse().unwrap(),
+                "ffff:ffff:ffff:ffff::".parse().unwrap(),
+            ),
+        ),
+    ] {
+        let parsed: Network = serde_json::from_value(json!(text)).unwrap();
+        assert_eq!(parsed, network);
+    }
+    assert!(serde_json::from_str::<Network>("\"10.0.0.0/33\"").is_err());
+
+    assert_eq!(
+        serde_json::to_string(&[Lookup::File, Lookup::Bind, Lookup::Extra("yp".into())]).unwrap(),
+        r#"["file","bind","yp"]"#
+    );
+    assert_eq!(
+        serde_json::from_str::<Vec<Lookup>>(r#"["bind","yp"]"#).unwrap(),
+        [Lookup::Bind, Lookup::Extra("yp".into())]
+    );
+    assert_eq!(
+        serde_json::from_str::<Vec<Family>>(r#"["inet4","inet6"]"#).unwrap(),
+        [Family::Inet4, Family::Inet6]
+    );
+}
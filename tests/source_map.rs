@@ -0,0 +1,115 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, Span};
+use std::path::Path;
+
+fn text<'a>(buf: &'a [u8], span: &Span) -> &'a str {
+    std::str::from_utf8(&buf[span.start..span.end]).unwrap()
+}
+
+#[test]
+fn test_spans() {
+    let buf = b"# generated\nnameserver 10.0.0.1\ndomain example.com\nnameserver  bogus\nsearch a.example b.example ; comment\nsortlist 10.0.0.0/255.0.0.0\noptions rotate ndots:x strict-error\nnameserver ::1\n";
+    let (config, errors) =
+        Config::parse_with_source_map(buf, Some(Path::new("tests/resolv.conf-simple")));
+    assert_eq!(errors.len(), 3);
+    let map = config.source_map().unwrap();
+
+    assert_eq!(
+        map.file.as_deref(),
+        Some(Path::new("tests/resolv.conf-simple"))
+    );
+    assert_eq!(map.nameservers.len(), config.nameservers.len());
+    assert_eq!(
+        map.nameservers
+            .iter()
+            .map(|s| (s.line, text(buf, s)))
+            .collect::<Vec<_>>(),
+        [(1, "10.0.0.1"), (7, "::1")]
+    );
+    assert_eq!(text(buf, &map.domain.unwrap()), "example.com");
+    assert_eq!(
+        map.search.iter().map(|s| text(buf, s)).collect::<Vec<_>>(),
+        ["a.example", "b.example"]
+    );
+    // the `search` on line 4 won over the `domain` on line 2
+    assert!(map.search[0].line > map.domain.unwrap().line);
+    assert_eq!(text(buf, &map.sortlist[0]), "10.0.0.0/255.0.0.0");
+    assert_eq!(text(buf, &map.options["rotate"]), "rotate");
+    assert_eq!(text(buf, &map.options["strict-error"]), "strict-error");
+    assert!(!map.options.contains_key("ndots"));
+    assert!(map.overridden.is_empty());
+}
+
+#[test]
+fn test_overridden() {
+    let buf = b"domain a.example\noptions ndots:2 timeout:3\ndomain b.example\noptions ndots:5\n";
+    let (config, _) = Config::parse_with_source_map(buf, None);
+    let map = config.source_map().unwrap();
+    assert_eq!(map.options["ndots"].line, 3);
+    assert_eq!(map.options["timeout"].line, 1);
+    assert_eq!(
+        map.overridden
+            .iter()
+            .map(|s| (s.line, text(buf, s)))
+            .collect::<Vec<_>>(),
+        [(0, "a.example"), (1, "ndots:2")]
+    );
+}
+
+#[test]
+fn test_other_directives() {
+    let buf = b"lookup file bind\nfamily inet6 bogus inet4\noptions foo:1 ndots:2 foo:2 bar\nx-custom a  b ; c\nlookup yp\nempty-directive\n";
+    let (config, errors) = Config::parse_with_source_map(buf, None);
+    assert_eq!(errors.len(), 6);
+    let map = config.source_map().unwrap();
+    let texts = |spans: &[Span]| {
+        spans
+            .iter()
+            .map(|s| (s.line, text(buf, s)))
+            .collect::<Vec<_>>()
+    };
+
+    assert_eq!(map.lookup.len(), config.lookup.len());
+    assert_eq!(texts(&map.lookup), [(0, "file"), (0, "bind"), (4, "yp")]);
+    assert_eq!(map.family.len(), config.family.len());
+    assert_eq!(texts(&map.family), [(1, "inet6"), (1, "inet4")]);
+    assert_eq!(map.unknown_options.len(), config.unknown_options.len());
+    assert_eq!(
+        texts(&map.unknown_options),
+        [(2, "foo:1"), (2, "foo:2"), (2, "bar")]
+    );
+    assert_eq!(text(buf, &map.options["foo"]), "foo:2");
+    assert_eq!(text(buf, &map.options["ndots"]), "ndots:2");
+    assert_eq!(
+        map.unknown_directives.len(),
+        config.unknown_directives.len()
+    );
+    assert_eq!(
+        texts(&map.unknown_directives),
+        [(3, "x-custom a  b"), (5, "empty-directive")]
+    );
+    assert_eq!(texts(&map.overridden), [(2, "foo:1")]);
+}
+
+#[test]
+fn test_glibc_normalize() {
+    let buf =
+        b"nameserver 10.0.0.1\nnameserver 10.0.0.2\nnameserver 10.0.0.3\nnameserver 10.0.0.4\n";
+    let (mut config, _) = Config::parse_with_source_map(buf, None);
+    config.glibc_normalize();
+    let map = config.source_map().unwrap();
+    assert_eq!(map.nameservers.len(), 3);
+    assert_eq!(map.overridden.len(), 1);
+    assert_eq!(text(buf, &map.overridden[0]), "10.0.0.4");
+}
+
+#[test]
+fn test_not_compared() {
+    let buf = b"nameserver 10.0.0.1\n";
+    let (mapped, _) = Config::parse_with_source_map(buf, None);
+    let plain = Config::parse(buf).unwrap();
+    assert!(plain.source_map().is_none());
+    assert_eq!(mapped, plain);
+    assert_eq!(mapped.to_string(), plain.to_string());
+}
@@ -0,0 +1,91 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, LinkDnsTable, ScopedIp, ScutilDns, SplitDnsTable};
+use std::fs;
+use std::path::Path;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+#[test]
+fn test_longest_suffix() {
+    let public = Config::parse("nameserver 1.1.1.1\nnameserver 9.9.9.9").unwrap();
+    let corp = Config::parse("nameserver 10.1.1.1").unwrap();
+    let lab = Config::parse("nameserver 10.2.2.2").unwrap();
+    let table = SplitDnsTable::from_configs(vec![
+        (&public, &[][..]),
+        (&corp, &["corp.example.com", "10.in-addr.arpa."][..]),
+        (&lab, &["Lab.Corp.Example.com"][..]),
+    ]);
+
+    assert_eq!(
+        table.route("www.example.com"),
+        &[ip("1.1.1.1"), ip("9.9.9.9")]
+    );
+    assert_eq!(table.route("corp.example.com"), &[ip("10.1.1.1")]);
+    assert_eq!(table.route("GIT.corp.example.com."), &[ip("10.1.1.1")]);
+    assert_eq!(table.route("host.lab.corp.example.com"), &[ip("10.2.2.2")]);
+    assert_eq!(table.route("1.0.0.10.in-addr.arpa"), &[ip("10.1.1.1")]);
+    assert_eq!(table.route("notcorp.example.com"), table.default_route());
+    assert_eq!(
+        table.matching_domain("x.lab.corp.example.com"),
+        Some("lab.corp.example.com")
+    );
+    assert_eq!(table.matching_domain("example.com"), None);
+    assert_eq!(table.routes().count(), 3);
+}
+
+#[test]
+fn test_merge_routes() {
+    let mut table = SplitDnsTable::new();
+    table.add_route("example.com", &[ip("10.0.0.1")]);
+    table.add_route("EXAMPLE.com.", &[ip("10.0.0.1"), ip("10.0.0.2")]);
+    table.add_route(".", &[ip("1.1.1.1")]);
+    assert_eq!(
+        table.route("example.com"),
+        &[ip("10.0.0.1"), ip("10.0.0.2")]
+    );
+    assert_eq!(table.route("example.org"), &[ip("1.1.1.1")]);
+    assert!(SplitDnsTable::new().route("example.org").is_empty());
+}
+
+#[test]
+fn test_from_scutil() {
+    let data = fs::read_to_string("tests/scutil-dns-macos").unwrap();
+    let dns = ScutilDns::parse(&data).unwrap();
+    let table = SplitDnsTable::from(&dns);
+
+    assert_eq!(
+        table.route("git.corp.example.com"),
+        &[ip("10.1.1.1"), ip("10.1.1.2")]
+    );
+    assert!(table.route("printer.local").is_empty());
+    assert_eq!(
+        table.route("example.org"),
+        &[ip("192.168.1.1"), ip("fe80::1%en0")]
+    );
+}
+
+#[test]
+fn test_from_link_table() {
+    let links = LinkDnsTable::load_from(
+        Path::new("tests/systemd/run/resolve/netif"),
+        Path::new("tests/systemd/run/netif/links"),
+    )
+    .unwrap();
+    let table = SplitDnsTable::from(&links);
+
+    assert_eq!(
+        table.route("git.corp.example.com"),
+        &[ip("10.1.1.1"), ip("10.1.1.2")]
+    );
+    assert_eq!(
+        table.route("nas.home.example.com"),
+        &[ip("192.168.1.1"), ip("fe80::1%2")]
+    );
+    assert_eq!(
+        table.route("example.org"),
+        &[ip("192.168.1.1"), ip("fe80::1%2")]
+    );
+}
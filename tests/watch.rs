@@ -0,0 +1,275 @@
+#![cfg(target_os = "linux")]
+
+extern crate resolv_conf;
+extern crate tempfile;
+
+use resolv_conf::{ConfigWatcher, ScopedIp};
+use std::fs;
+use std::io;
+use std::os::unix::fs::symlink;
+use std::path::Path;
+use std::thread;
+use std::time::Duration;
+
+fn ip(s: &str) -> ScopedIp {
+    s.parse().unwrap()
+}
+
+fn watch(path: &Path) -> ConfigWatcher {
+    ConfigWatcher::new(path)
+        .unwrap()
+        .with_debounce(Duration::from_millis(50))
+}
+
+fn next_nameservers(watcher: &mut ConfigWatcher) -> Vec<ScopedIp> {
+    watcher
+        .next_timeout(Duration::from_secs(5))
+        .expect("no change noticed")
+        .unwrap()
+        .nameservers
+}
+
+#[test]
+fn test_watch_write_and_rename() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let mut watcher = watch(&path);
+
+    fs::write(&path, "nameserver 10.0.0.2\n").unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+
+    let tmp = dir.path().join("resolv.conf.tmp");
+    fs::write(&tmp, "nameserver 10.0.0.3\n").unwrap();
+    fs::rename(&tmp, &path).unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.3")]);
+
+    // other files of the directory are ignored
+    fs::write(dir.path().join("hosts"), "127.0.0.1 localhost\n").unwrap();
+    assert!(watcher.next_timeout(Duration::from_millis(200)).is_none());
+}
+
+#[test]
+fn test_watch_delete_and_recreate() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let mut watcher = watch(&path);
+
+    fs::remove_file(&path).unwrap();
+    let err = watcher
+        .next_timeout(Duration::from_secs(5))
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::NotFound);
+
+    fs::write(&path, "nameserver 10.0.0.2\n").unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+}
+
+#[test]
+fn test_watch_debounce() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "").unwrap();
+    let mut watcher = watch(&path);
+
+    for i in 1..=5 {
+        fs::write(&path, format!("nameserver 10.0.0.{}\n", i)).unwrap();
+    }
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.5")]);
+    assert!(watcher.next_timeout(Duration::from_millis(200)).is_none());
+}
+
+#[test]
+fn test_watch_symlink_chain() {
+    let etc = tempfile::tempdir().unwrap();
+    let run = tempfile::tempdir().unwrap();
+    let first = run.path().join("first.conf");
+    let second = run.path().join("second.conf");
+    fs::write(&first, "nameserver 10.0.0.1\n").unwrap();
+    fs::write(&second, "nameserver 10.0.0.2\n").unwrap();
+    let path = etc.path().join("resolv.conf");
+    symlink(&first, &path).unwrap();
+
+    let mut watcher = watch(&path);
+    assert_eq!(watcher.watched_files(), vec![path.clone(), first.clone()]);
+
+    // the target changes
+    fs::write(&first, "nameserver 10.0.0.3\n").unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.3")]);
+
+    // the symlink is replaced
+    let tmp = etc.path().join("resolv.conf.tmp");
+    symlink(&second, &tmp).unwrap();
+    fs::rename(&tmp, &path).unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+    assert_eq!(watcher.watched_files(), vec![path.clone(), second.clone()]);
+
+    // the old target is not relevant anymore
+    fs::write(&first, "nameserver 10.0.0.4\n").unwrap();
+    assert!(watcher.next_timeout(Duration::from_millis(200)).is_none());
+
+    fs::write(&second, "nameserver 10.0.0.5\n").unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.5")]);
+}
+
+#[test]
+fn test_watch_debounce_ignores_other_files() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "").unwrap();
+    let mut watcher = ConfigWatcher::new(&path)
+        .unwrap()
+        .with_debounce(Duration::from_millis(300));
+
+    let writer = {
+        let dir = dir.path().to_owned();
+        let path = path.clone();
+        thread::spawn(move || {
+            fs::write(
+                &path,
+                "nameserver 10.0.0.1
+",
+            )
+            .unwrap();
+            thread::sleep(Duration::from_millis(100));
+            // an editor swap file, in the middle of the burst
+            fs::write(dir.join(".resolv.conf.swp"), "").unwrap();
+            thread::sleep(Duration::from_millis(100));
+            fs::write(
+                &path,
+                "nameserver 10.0.0.2
+",
+            )
+            .unwrap();
+        })
+    };
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+    writer.join().unwrap();
+    assert!(watcher.next_timeout(Duration::from_millis(500)).is_none());
+}
+
+#[test]
+fn test_watch_retarget_across_directories() {
+    let etc = tempfile::tempdir().unwrap();
+    let first_dir = tempfile::tempdir().unwrap();
+    let second_dir = tempfile::tempdir().unwrap();
+    let first = first_dir.path().join("resolv.conf");
+    let second = second_dir.path().join("resolv.conf");
+    fs::write(&first, "nameserver 10.0.0.1\n").unwrap();
+    fs::write(&second, "nameserver 10.0.0.2\n").unwrap();
+    let path = etc.path().join("resolv.conf");
+    symlink(&first, &path).unwrap();
+    let mut watcher = watch(&path);
+
+    let tmp = etc.path().join("resolv.conf.tmp");
+    symlink(&second, &tmp).unwrap();
+    fs::rename(&tmp, &path).unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+    // dropping the watch of the old directory is not a change
+    assert!(watcher.next_timeout(Duration::from_millis(300)).is_none());
+}
+
+#[test]
+fn test_watch_invalid_file() {
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let mut watcher = watch(&path);
+
+    fs::write(&path, "nameserver not-an-ip\n").unwrap();
+    let err = watcher
+        .next_timeout(Duration::from_secs(5))
+        .unwrap()
+        .unwrap_err();
+    assert_eq!(err.kind(), io::ErrorKind::InvalidData);
+
+    fs::write(&path, "nameserver 10.0.0.2\n").unwrap();
+    assert_eq!(next_nameservers(&mut watcher), vec![ip("10.0.0.2")]);
+}
+
+#[test]
+fn test_watch_directory_removed() {
+    let dir = tempfile::tempdir().unwrap();
+    let etc = dir.path().join("etc");
+    fs::create_dir(&etc).unwrap();
+    let path = etc.join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let mut watcher = watch(&path);
+
+    fs::remove_dir_all(&etc).unwrap();
+    let mut errors = 0;
+    while let Some(item) = watcher.next_timeout(Duration::from_secs(5)) {
+        assert_eq!(item.unwrap_err().kind(), io::ErrorKind::NotFound);
+        errors += 1;
+    }
+    assert!(errors > 0);
+    assert!(watcher.has_failed());
+    assert!(watcher.next().is_none());
+}
+
+#[cfg(feature = "stream")]
+#[test]
+fn test_watch_stream() {
+    use std::future::Future;
+    use std::pin::Pin;
+    use std::sync::Arc;
+    use std::task::{Context, Poll, Wake};
+    use std::thread::{self, Thread};
+
+    struct ThreadWaker(Thread);
+
+    impl Wake for ThreadWaker {
+        fn wake(self: Arc<Self>) {
+            self.0.unpark();
+        }
+    }
+
+    struct Next<'a, S>(&'a mut S);
+
+    impl<S: futures_core::Stream + Unpin> Future for Next<'_, S> {
+        type Output = Option<S::Item>;
+
+        fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+            Pin::new(&mut *self.0).poll_next(cx)
+        }
+    }
+
+    fn block_on<F: Future>(future: F) -> F::Output {
+        let mut future = Box::pin(future);
+        let waker = Arc::new(ThreadWaker(thread::current())).into();
+        let mut cx = Context::from_waker(&waker);
+        loop {
+            match future.as_mut().poll(&mut cx) {
+                Poll::Ready(output) => return output,
+                Poll::Pending => thread::park(),
+            }
+        }
+    }
+
+    let dir = tempfile::tempdir().unwrap();
+    let path = dir.path().join("resolv.conf");
+    fs::write(&path, "nameserver 10.0.0.1\n").unwrap();
+    let mut stream = watch(&path).into_stream();
+
+    fs::write(&path, "nameserver 10.0.0.2\n").unwrap();
+    let config = block_on(Next(&mut stream)).unwrap().unwrap();
+    assert_eq!(config.nameservers, vec![ip("10.0.0.2")]);
+
+    // the same error repeated while nobody polls is queued once
+    for _ in 0..3 {
+        fs::write(&path, "nameserver not-an-ip\n").unwrap();
+        thread::sleep(Duration::from_millis(400));
+    }
+    fs::write(&path, "nameserver 10.0.0.3\n").unwrap();
+    thread::sleep(Duration::from_millis(400));
+    assert!(block_on(Next(&mut stream)).unwrap().is_err());
+    let config = block_on(Next(&mut stream)).unwrap().unwrap();
+    assert_eq!(config.nameservers, vec![ip("10.0.0.3")]);
+
+    // the stream ends once the directory of the file is gone
+    drop(dir);
+    assert!(block_on(Next(&mut stream)).unwrap().is_err());
+    assert!(block_on(Next(&mut stream)).is_none());
+}
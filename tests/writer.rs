@@ -0,0 +1,117 @@
+extern crate resolv_conf;
+
+use resolv_conf::{Config, Dialect, MaskStyle, Writer};
+use std::fs;
+
+fn parse(buf: &str) -> Config {
+    Config::parse_with_errors(buf.as_bytes()).0
+}
+
+#[test]
+fn test_default_is_display() {
+    for path in [
+        "tests/resolv.conf-simple",
+        "tests/resolv.conf-linux",
+        "tests/resolv.conf-macos",
+        "tests/resolv.conf-openbsd",
+        "tests/resolv.conf-systemd-stub",
+    ] {
+        let config = Config::parse_with_errors(&fs::read(path).unwrap()).0;
+        assert_eq!(Writer::new().format(&config), config.to_string());
+
+        let mut buf = Vec::new();
+        Writer::new().write(&config, &mut buf).unwrap();
+        assert_eq!(buf, config.to_string().into_bytes());
+    }
+}
+
+#[test]
+fn test_group_options() {
+    let config = parse("options ndots:3 rotate edns0 foo:1\noptions trust-ad");
+    assert_eq!(
+        Writer::new().group_options(usize::MAX).format(&config),
+        "options ndots:3 rotate edns0 trust-ad foo:1\n"
+    );
+    assert_eq!(
+        Writer::new().group_options(20).format(&config),
+        "options ndots:3\noptions rotate edns0\noptions trust-ad\noptions foo:1\n"
+    );
+    // an option longer than the width gets its own line
+    assert_eq!(
+        Writer::new()
+            .group_options(5)
+            .format(&parse("options rotate")),
+        "options rotate\n"
+    );
+    assert_eq!(Writer::new().group_options(80).format(&Config::new()), "");
+
+    let text = Writer::new().group_options(20).format(&config);
+    assert_eq!(parse(&text), config);
+}
+
+#[test]
+fn test_header_and_defaults() {
+    let config = parse("nameserver 10.0.0.1");
+    assert_eq!(
+        Writer::new()
+            .header("Generated by a tool\n\nDo not edit")
+            .explicit_defaults(true)
+            .format(&config),
+        "# Generated by a tool
+#
+# Do not edit
+nameserver 10.0.0.1
+options ndots:1
+options timeout:5
+options attempts:2
+"
+    );
+}
+
+#[test]
+fn test_masks() {
+    let config =
+        parse("sortlist 10.0.0.0/255.0.0.0 192.168.1.1 10.0.0.0/255.0.255.0 fe80::/ffff:ffff::");
+    assert_eq!(
+        Writer::new().mask_style(MaskStyle::Cidr).format(&config),
+        "sortlist 10.0.0.0/8 192.168.1.1/32 10.0.0.0/255.0.255.0 fe80::/32\n"
+    );
+    assert_eq!(
+        Writer::new().mask_style(MaskStyle::Dotted).format(&config),
+        config.to_string()
+    );
+}
+
+#[test]
+fn test_dialects() {
+    let config = parse("options ndots:2 rotate foo\nlookup file bind\nfamily inet4");
+    assert_eq!(
+        Writer::new().dialect(Dialect::Musl).format(&config),
+        "options ndots:2\n"
+    );
+    assert_eq!(
+        Writer::new().dialect(Dialect::Glibc).format(&config),
+        "options ndots:2\noptions rotate\noptions foo\n"
+    );
+    assert_eq!(
+        Writer::new().dialect(Dialect::OpenBsd).format(&config),
+        config.to_string()
+    );
+}
+
+#[test]
+fn test_musl() {
+    let config = Config::parse_with_errors(
+        b"nameserver 10.0.0.1\nsearch example.com\nsortlist 10.0.0.0/255.0.0.0\n\
+          options debug timeout:3 edns0 foo:1\nx-custom a b\n",
+    )
+    .0;
+    assert_eq!(
+        Writer::new().dialect(Dialect::Musl).format(&config),
+        "nameserver 10.0.0.1\nsearch example.com\noptions timeout:3\n"
+    );
+    assert_eq!(
+        Writer::new().dialect(Dialect::Glibc).format(&config),
+        config.to_string()
+    );
+}